@@ -0,0 +1,23 @@
+-- Create summary_jobs table for asynchronous summary generation
+CREATE TABLE IF NOT EXISTS summary_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    book_id VARCHAR(100) NOT NULL,
+    language VARCHAR(10) NOT NULL,
+    style VARCHAR(20) NOT NULL,
+    max_pages INTEGER NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'queued',
+    summary_id UUID REFERENCES summaries(id) ON DELETE SET NULL,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Create index on status for picking up unfinished jobs on startup
+CREATE INDEX IF NOT EXISTS idx_summary_jobs_status
+    ON summary_jobs(status);
+
+-- Create trigger to automatically update updated_at
+CREATE TRIGGER update_summary_jobs_updated_at
+    BEFORE UPDATE ON summary_jobs
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
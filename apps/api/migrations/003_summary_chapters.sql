@@ -0,0 +1,15 @@
+-- Create summary_chapters table for chapter-by-chapter summaries
+CREATE TABLE IF NOT EXISTS summary_chapters (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    summary_id UUID NOT NULL REFERENCES summaries(id) ON DELETE CASCADE,
+    chapter_index INTEGER NOT NULL,
+    title VARCHAR(500) NOT NULL,
+    summary_text TEXT NOT NULL,
+    word_count INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    UNIQUE (summary_id, chapter_index)
+);
+
+-- Create index on summary_id for fetching a summary's chapters in order
+CREATE INDEX IF NOT EXISTS idx_summary_chapters_summary
+    ON summary_chapters(summary_id, chapter_index);
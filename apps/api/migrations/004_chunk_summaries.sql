@@ -0,0 +1,9 @@
+-- Create chunk_summaries table so finished chunk-level work survives failed runs
+CREATE TABLE IF NOT EXISTS chunk_summaries (
+    chunk_hash VARCHAR(64) NOT NULL,
+    model VARCHAR(200) NOT NULL,
+    style VARCHAR(20) NOT NULL,
+    summary_text TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (chunk_hash, model, style)
+);
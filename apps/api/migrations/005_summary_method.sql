@@ -0,0 +1,3 @@
+-- Record whether a summary came from the model or the offline extractive fallback
+ALTER TABLE summaries
+    ADD COLUMN IF NOT EXISTS method VARCHAR(20) NOT NULL DEFAULT 'abstractive';
@@ -0,0 +1,9 @@
+-- Record where a summary's input came from and how the summary was generated
+ALTER TABLE summaries
+    ADD COLUMN IF NOT EXISTS content_origin VARCHAR(30) NOT NULL DEFAULT 'unknown',
+    ADD COLUMN IF NOT EXISTS input_chars INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS truncated BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS model VARCHAR(200) NOT NULL DEFAULT 'unknown',
+    ADD COLUMN IF NOT EXISTS generation_params JSONB NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS chunk_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS fallback_level INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,22 @@
+-- Keep every generated summary as a numbered version per book, language and style
+ALTER TABLE summaries
+    ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 1;
+
+-- Number existing summaries oldest first
+UPDATE summaries s
+SET version = v.version
+FROM (
+    SELECT id, ROW_NUMBER() OVER (
+        PARTITION BY book_id, language, style ORDER BY created_at
+    ) AS version
+    FROM summaries
+) v
+WHERE s.id = v.id;
+
+-- Create index for fetching the latest version and the version history
+CREATE INDEX IF NOT EXISTS idx_summaries_book_lang_style_version
+    ON summaries(book_id, language, style, version DESC);
+
+-- Let a job skip stored summaries and always produce a new version
+ALTER TABLE summary_jobs
+    ADD COLUMN IF NOT EXISTS force_regenerate BOOLEAN NOT NULL DEFAULT FALSE;
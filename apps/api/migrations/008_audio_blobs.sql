@@ -0,0 +1,12 @@
+-- Audio bytes move out of Postgres into the configured blob store.
+-- Existing base64 data URLs stay in file_url until the API copies them
+-- to the blob store on startup, after which file_url is cleared.
+ALTER TABLE audio_files
+    ALTER COLUMN file_url DROP NOT NULL,
+    ADD COLUMN IF NOT EXISTS blob_key TEXT,
+    ADD COLUMN IF NOT EXISTS content_type VARCHAR(100) NOT NULL DEFAULT 'audio/wav';
+
+-- Create index for finding rows that still hold inline audio
+CREATE INDEX IF NOT EXISTS idx_audio_files_inline
+    ON audio_files(created_at)
+    WHERE blob_key IS NULL;
@@ -0,0 +1,5 @@
+-- Stream parameters read from the generated audio; bit_depth is NULL for lossy formats
+ALTER TABLE audio_files
+    ADD COLUMN IF NOT EXISTS sample_rate INTEGER,
+    ADD COLUMN IF NOT EXISTS channels SMALLINT,
+    ADD COLUMN IF NOT EXISTS bit_depth SMALLINT;
@@ -0,0 +1,3 @@
+-- Audio is looked up per summary, language and voice
+CREATE INDEX IF NOT EXISTS idx_audio_summary_lang_voice
+    ON audio_files(summary_id, language, voice_type);
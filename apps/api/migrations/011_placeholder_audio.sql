@@ -0,0 +1,11 @@
+-- Flag the synthetic tone stored when every TTS model failed and schedule
+-- background retries until real speech replaces it
+ALTER TABLE audio_files
+    ADD COLUMN IF NOT EXISTS is_placeholder BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS retry_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS next_retry_at TIMESTAMP WITH TIME ZONE;
+
+-- Create index for the retry worker's due-placeholder scan
+CREATE INDEX IF NOT EXISTS idx_audio_files_placeholder_retry
+    ON audio_files(next_retry_at)
+    WHERE is_placeholder;
@@ -0,0 +1,4 @@
+-- Sentence and word timings recorded at synthesis, with character offsets into
+-- the summary text; NULL for audio generated before timings were recorded
+ALTER TABLE audio_files
+    ADD COLUMN IF NOT EXISTS timings JSONB;
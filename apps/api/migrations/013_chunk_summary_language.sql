@@ -0,0 +1,7 @@
+-- Key stored chunk summaries on the language they were written in, since the same
+-- source text is summarized differently per language
+ALTER TABLE chunk_summaries
+    ADD COLUMN IF NOT EXISTS language VARCHAR(10) NOT NULL DEFAULT 'en';
+
+ALTER TABLE chunk_summaries DROP CONSTRAINT IF EXISTS chunk_summaries_pkey;
+ALTER TABLE chunk_summaries ADD PRIMARY KEY (chunk_hash, model, style, language);
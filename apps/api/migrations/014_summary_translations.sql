@@ -0,0 +1,8 @@
+-- Link translated summaries to the summary they were translated from
+ALTER TABLE summaries
+    ADD COLUMN IF NOT EXISTS translated_from UUID REFERENCES summaries(id) ON DELETE CASCADE;
+
+-- Create index for looking up an existing translation into a language
+CREATE INDEX IF NOT EXISTS idx_summaries_translated_from
+    ON summaries(translated_from, language)
+    WHERE translated_from IS NOT NULL;
@@ -0,0 +1,3 @@
+-- Record the language identified in the content a summary was generated from
+ALTER TABLE summaries
+    ADD COLUMN IF NOT EXISTS detected_language VARCHAR(10);
@@ -0,0 +1,8 @@
+-- Record the page budget each summary was written to, so stored summaries are only
+-- reused for requests with the same budget
+ALTER TABLE summaries
+    ADD COLUMN IF NOT EXISTS max_pages INTEGER NOT NULL DEFAULT 3;
+
+-- Create index for finding the latest summary written to a page budget
+CREATE INDEX IF NOT EXISTS idx_summaries_book_lang_style_pages
+    ON summaries(book_id, language, style, max_pages, version DESC);
@@ -0,0 +1,7 @@
+-- Structured summaries keep their sections as JSONB next to the plain-text rendering
+ALTER TABLE summaries
+    ADD COLUMN IF NOT EXISTS format VARCHAR(20) NOT NULL DEFAULT 'plain',
+    ADD COLUMN IF NOT EXISTS structured JSONB;
+
+ALTER TABLE summary_jobs
+    ADD COLUMN IF NOT EXISTS format VARCHAR(20) NOT NULL DEFAULT 'plain';
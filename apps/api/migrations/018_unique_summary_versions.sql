@@ -0,0 +1,16 @@
+-- Concurrent jobs could store the same version number twice; renumber such duplicates
+-- in their original order before making versions unique
+UPDATE summaries s
+SET version = v.version
+FROM (
+    SELECT id, ROW_NUMBER() OVER (
+        PARTITION BY book_id, language, style ORDER BY version, created_at, id
+    ) AS version
+    FROM summaries
+) v
+WHERE s.id = v.id AND s.version <> v.version;
+
+DROP INDEX IF EXISTS idx_summaries_book_lang_style_version;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_summaries_book_lang_style_version_unique
+    ON summaries(book_id, language, style, version DESC);
@@ -0,0 +1,9 @@
+-- Keep the plain-text rendering of structured summaries apart from the summary prose,
+-- which stays in summary_text within its length budget
+ALTER TABLE summaries
+    ADD COLUMN IF NOT EXISTS structured_text TEXT;
+
+-- Earlier structured summaries stored their rendering in summary_text
+UPDATE summaries
+SET structured_text = summary_text
+WHERE structured IS NOT NULL AND structured_text IS NULL;
@@ -1,22 +1,32 @@
 use axum::{
     extract::{Path, Query, State},
-    http::{header, StatusCode},
-    response::IntoResponse,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
 };
-use base64::{engine::general_purpose, Engine as _};
+use chrono::Utc;
 use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::{
-    models::CreateAudioFile,
-    services::huggingface::{HuggingFaceClient, TTSService},
+    models::{AudioFile, AudioTimings, AudioTimingsResponse, Voice, VoicesQuery},
+    services::{
+        jobs::{audio_request_key, generate_audio, stored_voice_types},
+        storage::decode_data_url,
+    },
     utils::{
+        byte_range::{parse_range, RangeRequest},
         errors::{AppError, Result},
-        validators,
+        timing, validators,
     },
     AppState,
 };
 
+// Audio is never rewritten under the same ID, so clients may cache it indefinitely
+const AUDIO_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+// Placeholder tones are replaced by real speech under a new ID and must not be kept
+const PLACEHOLDER_CACHE_CONTROL: &str = "no-store";
+
 #[derive(Deserialize)]
 pub struct AudioQuery {
     pub language: String,
@@ -36,7 +46,7 @@ pub async fn get_audio(
         query.voice_type
     );
 
-    validators::validate_language(&query.language)?;
+    validators::validate_language(&state.languages, &query.language)?;
 
     let summary_uuid = Uuid::parse_str(&summary_id)
         .map_err(|_| AppError::InvalidInput("Invalid summary ID format".to_string()))?;
@@ -50,196 +60,228 @@ pub async fn get_audio(
             AppError::SummaryNotFound
         })?;
 
+    let voice = state
+        .tts
+        .resolve_voice(&query.language, query.voice_type.as_deref())?;
+
     // Check for existing audio file first
     if let Some(existing_audio) = state
         .db
-        .get_audio_by_summary(summary_uuid, &query.language)
+        .get_audio_by_summary(summary_uuid, &query.language, &stored_voice_types(&voice))
         .await?
     {
         tracing::info!(
-            "Returning existing audio for summary: {} in language: {}",
+            "Returning existing audio for summary: {} in language: {} with voice: {}",
             summary_id,
-            query.language
+            query.language,
+            voice.id
         );
 
         return Ok((
             StatusCode::OK,
             [(header::CONTENT_TYPE, "application/json")],
-            serde_json::to_vec(&existing_audio.to_response()).unwrap(),
+            serde_json::to_vec(&existing_audio.into_response()).unwrap(),
         ));
     }
 
-    tracing::info!(
-        "No existing audio found, generating new audio for summary: {} in language: {}",
-        summary_id,
-        query.language
-    );
+    // A placeholder that is not yet due for a retry is served as is; the retry worker
+    // owns retries so requests do not keep hammering a failing TTS backend
+    if let Some(placeholder) = state
+        .db
+        .get_placeholder_audio(summary_uuid, &query.language, &stored_voice_types(&voice))
+        .await?
+        .filter(|placeholder| {
+            placeholder
+                .next_retry_at
+                .is_some_and(|next_retry_at| next_retry_at > Utc::now())
+        })
+    {
+        tracing::info!(
+            "Returning placeholder audio {} for summary: {} until its retry at {:?}",
+            placeholder.id,
+            summary_id,
+            placeholder.next_retry_at
+        );
 
-    // Validate summary has content for audio generation
-    if summary.summary_text.trim().is_empty() {
-        tracing::error!("Summary has no text content for audio generation");
-        return Err(AppError::InvalidInput(
-            "Summary contains no text for audio generation".to_string(),
+        return Ok((
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/json")],
+            serde_json::to_vec(&placeholder.into_response()).unwrap(),
         ));
     }
 
-    tracing::info!(
-        "Summary text preview (first 100 chars): {}",
-        &summary.summary_text.chars().take(100).collect::<String>()
-    );
-    tracing::info!(
-        "Summary text length: {} characters for audio generation",
-        summary.summary_text.len()
-    );
+    let request_key = audio_request_key(summary_uuid, &query.language, &voice.id);
 
-    let hf_client = HuggingFaceClient::new(
-        state.http_client.clone(),
-        state.config.hf_api_base_url.clone(),
-        state.config.hf_token.clone(),
-    );
+    // Concurrent requests for the same audio share one generation
+    let response = state
+        .audio_requests
+        .run(&request_key, || {
+            generate_audio(&state, &summary, &query.language, &voice)
+        })
+        .await?;
 
-    let tts_service = TTSService::new(hf_client);
+    Ok((
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/json")],
+        serde_json::to_vec(&response).unwrap(),
+    ))
+}
 
-    tracing::info!(
-        "Starting TTS generation for summary {} in language {} with {} characters",
-        summary_id,
-        query.language,
-        summary.summary_text.len()
-    );
+pub async fn list_voices(
+    State(state): State<AppState>,
+    Query(query): Query<VoicesQuery>,
+) -> Result<Json<Vec<Voice>>> {
+    if let Some(language) = &query.language {
+        validators::validate_language(&state.languages, language)?;
+    }
 
-    let audio_data = match tts_service
-        .generate_audio(&summary.summary_text, &query.language)
-        .await
-    {
-        Ok(data) => {
-            if data.is_empty() {
-                tracing::error!(
-                    "TTS service returned empty audio data for summary {}",
-                    summary_id
-                );
-                return Err(AppError::ServiceError(
-                    "Audio generation completed but produced no audio data. Please try again."
-                        .to_string(),
-                ));
-            }
-
-            tracing::info!(
-                "✓ Successfully generated audio: {} bytes for summary {} in language {}",
-                data.len(),
-                summary_id,
-                query.language
-            );
-            data
-        }
-        Err(e) => {
-            tracing::error!("==========================================");
-            tracing::error!(
-                "✗ TTS generation FAILED for summary {} in language {}: {}",
-                summary_id,
-                query.language,
-                e
-            );
-            tracing::error!("==========================================");
-
-            // Provide more user-friendly error messages based on error type
-            let user_error = match e {
-                crate::utils::errors::AppError::ExternalApi(ref msg) => {
-                    tracing::error!("External API error details: {}", msg);
-
-                    if msg.contains("Authentication") || msg.contains("401") || msg.contains("403")
-                    {
-                        "Audio service authentication failed. HuggingFace API token may be invalid or expired. Please check your HF_TOKEN configuration.".to_string()
-                    } else if msg.contains("timeout") || msg.contains("timed out") {
-                        "Audio generation timed out. The HuggingFace model may be loading (cold start). Please try again in a moment."
-                            .to_string()
-                    } else if msg.contains("rate limit") || msg.contains("429") {
-                        "Audio service rate limit exceeded. Please wait a moment and try again."
-                            .to_string()
-                    } else if msg.contains("model") || msg.contains("404") {
-                        format!("Audio generation model not found. Error: {}", msg)
-                    } else if msg.contains("Unable to generate audio") {
-                        format!("TTS generation failed: {}", msg)
-                    } else {
-                        format!("Audio generation service error: {}", msg)
-                    }
-                }
-                _ => format!("Audio generation failed: {}", e),
-            };
-
-            tracing::error!("Returning error to user: {}", user_error);
-            return Err(AppError::ServiceError(user_error));
+    Ok(Json(state.tts.voices(query.language.as_deref())))
+}
+
+/// Serve the raw bytes of a generated audio file, honouring `Range` requests so players can seek
+pub async fn stream_audio(
+    State(state): State<AppState>,
+    Path(audio_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response> {
+    let audio_file = find_audio(&state, &audio_id).await?;
+
+    let (content_type, data) = load_audio(&state, &audio_file).await?;
+
+    let cache_control = if audio_file.is_placeholder {
+        PLACEHOLDER_CACHE_CONTROL
+    } else {
+        AUDIO_CACHE_CONTROL
+    };
+    let total = data.len() as u64;
+    let range = headers
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok());
+
+    let response = match parse_range(range, total) {
+        RangeRequest::Full => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, content_type),
+                (header::CONTENT_LENGTH, total.to_string()),
+                (header::ACCEPT_RANGES, "bytes".to_string()),
+                (header::CACHE_CONTROL, cache_control.to_string()),
+            ],
+            data,
+        )
+            .into_response(),
+        RangeRequest::Partial(range) => {
+            let body = data[range.start as usize..=range.end as usize].to_vec();
+            (
+                StatusCode::PARTIAL_CONTENT,
+                [
+                    (header::CONTENT_TYPE, content_type),
+                    (header::CONTENT_LENGTH, range.size().to_string()),
+                    (header::CONTENT_RANGE, range.content_range(total)),
+                    (header::ACCEPT_RANGES, "bytes".to_string()),
+                    (header::CACHE_CONTROL, cache_control.to_string()),
+                ],
+                body,
+            )
+                .into_response()
         }
+        RangeRequest::Unsatisfiable => (
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            [
+                (header::CONTENT_RANGE, format!("bytes */{}", total)),
+                (header::ACCEPT_RANGES, "bytes".to_string()),
+            ],
+        )
+            .into_response(),
     };
 
-    let file_size_kb = (audio_data.len() / 1024) as i32;
+    Ok(response)
+}
 
-    tracing::info!("Encoding audio data to base64, size: {}KB", file_size_kb);
+/// Sentence and word timings for an audio file, with character offsets into its summary text
+pub async fn get_audio_timings(
+    State(state): State<AppState>,
+    Path(audio_id): Path<String>,
+) -> Result<Json<AudioTimingsResponse>> {
+    let audio_file = find_audio(&state, &audio_id).await?;
+    let (timings, estimated) = load_timings(&state, &audio_file).await?;
 
-    let audio_base64 = general_purpose::STANDARD.encode(&audio_data);
-    let data_url = format!("data:audio/wav;base64,{}", audio_base64);
+    Ok(Json(AudioTimingsResponse {
+        audio_id: audio_file.id,
+        summary_id: audio_file.summary_id,
+        duration_ms: audio_file.duration_ms,
+        estimated,
+        sentences: timings.sentences,
+        words: timings.words,
+    }))
+}
 
-    if audio_base64.len() < 100 {
-        tracing::error!(
-            "Generated base64 audio data is too small ({} chars) for summary {}",
-            audio_base64.len(),
-            summary_id
-        );
-        return Err(AppError::ServiceError(
-            "Generated audio appears to be corrupted. Please try again.".to_string(),
-        ));
-    }
+/// Sentence timings for an audio file as a WebVTT caption track
+pub async fn get_audio_captions(
+    State(state): State<AppState>,
+    Path(audio_id): Path<String>,
+) -> Result<Response> {
+    let audio_file = find_audio(&state, &audio_id).await?;
+    let (timings, _) = load_timings(&state, &audio_file).await?;
 
-    tracing::info!(
-        "Generated base64 audio data: {} characters for summary {}",
-        audio_base64.len(),
-        summary_id
-    );
+    Ok((
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/vtt; charset=utf-8")],
+        timing::to_webvtt(&timings),
+    )
+        .into_response())
+}
 
-    let create_audio = CreateAudioFile {
-        summary_id: summary_uuid,
-        language: query.language.clone(),
-        voice_type: query.voice_type.unwrap_or_else(|| "default".to_string()),
-        file_url: data_url,
-        duration_ms: Some(file_size_kb * 100), // Rough estimate: ~100ms per KB
-        file_size_kb: Some(file_size_kb),
-    };
+async fn find_audio(state: &AppState, audio_id: &str) -> Result<AudioFile> {
+    let audio_uuid = Uuid::parse_str(audio_id)
+        .map_err(|_| AppError::InvalidInput("Invalid audio ID format".to_string()))?;
 
-    let audio_file = match state.db.create_audio_file(create_audio).await {
-        Ok(file) => {
-            tracing::info!(
-                "Successfully saved audio file for summary: {} in language: {}",
-                summary_id,
-                query.language
-            );
-            file
-        }
-        Err(e) => {
-            tracing::error!(
-                "Failed to save audio file to database for summary {}: {}",
-                summary_id,
-                e
-            );
-            return Err(AppError::ServiceError(
-                "Audio was generated successfully but failed to save. Please try again."
-                    .to_string(),
-            ));
-        }
-    };
+    state
+        .db
+        .get_audio_by_id(audio_uuid)
+        .await?
+        .ok_or(AppError::AudioNotFound)
+}
 
-    let response = audio_file.to_response();
+/// Timings recorded at synthesis, or an estimate spread over the summary text for audio
+/// stored before timings were recorded. The flag is set when the timings are estimated.
+async fn load_timings(state: &AppState, audio_file: &AudioFile) -> Result<(AudioTimings, bool)> {
+    if let Some(timings) = audio_file.recorded_timings() {
+        // Placeholder tones carry no speech, so their timings are always estimates
+        return Ok((timings, audio_file.is_placeholder));
+    }
 
-    tracing::info!("==========================================");
-    tracing::info!(
-        "✓ Audio generation completed successfully - Summary: {}, Language: {}, Size: {}KB",
-        summary_id,
-        query.language,
-        file_size_kb
-    );
-    tracing::info!("==========================================");
+    let summary = state
+        .db
+        .get_summary_by_id(audio_file.summary_id)
+        .await?
+        .ok_or(AppError::SummaryNotFound)?;
+    let duration_ms = audio_file.duration_ms.unwrap_or(0).max(0) as u64;
+
+    let rules = state.languages.sentence_rules(&summary.language);
 
     Ok((
-        StatusCode::OK,
-        [(header::CONTENT_TYPE, "application/json")],
-        serde_json::to_vec(&response).unwrap(),
+        timing::estimate(summary.spoken_text(), rules, duration_ms),
+        true,
     ))
 }
+
+/// Read an audio file's bytes from the blob store, or from its legacy inline data URL
+async fn load_audio(state: &AppState, audio_file: &AudioFile) -> Result<(String, Vec<u8>)> {
+    if let Some(key) = &audio_file.blob_key {
+        let data = state.blobs.get(key).await?.ok_or_else(|| {
+            tracing::error!("Blob {} for audio {} is missing", key, audio_file.id);
+            AppError::AudioNotFound
+        })?;
+        return Ok((audio_file.content_type.clone(), data));
+    }
+
+    audio_file
+        .file_url
+        .as_deref()
+        .and_then(decode_data_url)
+        .ok_or_else(|| {
+            tracing::error!("Stored audio {} is not a valid data URL", audio_file.id);
+            AppError::ServiceError("Stored audio could not be read".to_string())
+        })
+}
@@ -5,9 +5,6 @@ use axum::{
 
 use crate::{
     models::BookDetail,
-    services::books::{
-        BookAggregatorService, GoogleBooksService, GutenbergService, OpenLibraryService,
-    },
     utils::errors::{AppError, Result},
     AppState,
 };
@@ -24,23 +21,9 @@ pub async fn get_book(
         return Ok(Json(cached));
     }
 
-    // Initialize services
-    let google_books = GoogleBooksService::new(
-        state.http_client.clone(),
-        state.config.google_books_api_key.clone(),
-    );
-
-    let open_library = OpenLibraryService::new(state.http_client.clone());
-
-    let gutenberg = GutenbergService::new(
-        state.http_client.clone(),
-        state.config.gutenberg_api_base_url.clone(),
-    );
-
-    let aggregator = BookAggregatorService::new(google_books, open_library, gutenberg);
-
     // Try to get book details from aggregator
-    let book_detail = aggregator
+    let book_detail = state
+        .books
         .get_book_details(&book_id)
         .await?
         .ok_or_else(|| AppError::BookNotFound(format!("Book with ID {} not found", book_id)))?;
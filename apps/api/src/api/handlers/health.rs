@@ -176,7 +176,7 @@ async fn check_google_books_health(state: &AppState) -> ServiceStatus {
         .config
         .google_books_api_key
         .as_ref()
-        .map_or(true, |key| key.is_empty())
+        .is_none_or(|key| key.is_empty())
     {
         return ServiceStatus {
             status: "disabled".to_string(),
@@ -191,7 +191,10 @@ async fn check_google_books_health(state: &AppState) -> ServiceStatus {
         Duration::from_secs(10),
         state
             .http_client
-            .get("https://www.googleapis.com/books/v1/volumes")
+            .get(format!(
+                "{}/volumes",
+                state.config.google_books_api_base_url
+            ))
             .query(&[("q", "test"), ("maxResults", "1")])
             .query(&[("key", state.config.google_books_api_key.as_ref().unwrap())])
             .send(),
@@ -242,7 +245,7 @@ async fn check_hugging_face_health(state: &AppState) -> ServiceStatus {
         Duration::from_secs(10),
         state
             .http_client
-            .get(&format!("{}/models", state.config.hf_api_base_url))
+            .get(format!("{}/models", state.config.hf_api_base_url))
             .header("Authorization", format!("Bearer {}", state.config.hf_token))
             .send(),
     )
@@ -0,0 +1,106 @@
+use axum::{
+    extract::{Path, State},
+    response::sse::{Event, KeepAlive, Sse},
+    Json,
+};
+use futures::{stream, Stream, StreamExt};
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+use crate::{
+    models::{JobResponse, JobStatus, SummaryEvent},
+    utils::errors::{AppError, Result},
+    AppState,
+};
+
+pub async fn get_job(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> Result<Json<JobResponse>> {
+    let job_uuid = Uuid::parse_str(&job_id)
+        .map_err(|_| AppError::InvalidInput("Invalid job ID format".to_string()))?;
+
+    let job = state
+        .db
+        .get_summary_job(job_uuid)
+        .await?
+        .ok_or(AppError::JobNotFound)?;
+
+    let summary = match job.summary_id {
+        Some(summary_id) => state
+            .db
+            .get_summary_by_id(summary_id)
+            .await?
+            .map(|summary| summary.into_response()),
+        None => None,
+    };
+
+    Ok(Json(job.into_response(summary)))
+}
+
+/// Stream live progress for a summary job as Server-Sent Events.
+///
+/// The stream starts with the job's current status. Finished jobs get their final
+/// `completed` or `failed` event straight away; running jobs stream until they finish.
+pub async fn get_job_events(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> Result<Sse<impl Stream<Item = std::result::Result<Event, axum::Error>>>> {
+    let job_uuid = Uuid::parse_str(&job_id)
+        .map_err(|_| AppError::InvalidInput("Invalid job ID format".to_string()))?;
+
+    // Subscribe before reading the row so no event between the two is missed
+    let receiver = state.summary_jobs.events().subscribe(job_uuid);
+
+    let job = state
+        .db
+        .get_summary_job(job_uuid)
+        .await?
+        .ok_or(AppError::JobNotFound)?;
+
+    let status: JobStatus = job.status.parse().map_err(AppError::DatabaseError)?;
+
+    let mut initial = vec![SummaryEvent::Status { status }];
+    match status {
+        JobStatus::Done => {
+            let summary_id = job.summary_id.ok_or(AppError::SummaryNotFound)?;
+            let summary = state
+                .db
+                .get_summary_by_id(summary_id)
+                .await?
+                .ok_or(AppError::SummaryNotFound)?;
+            initial.push(SummaryEvent::Completed {
+                summary: Box::new(summary.into_response()),
+            });
+        }
+        JobStatus::Failed => initial.push(SummaryEvent::Failed {
+            error: job
+                .error
+                .unwrap_or_else(|| "Summary generation failed".to_string()),
+        }),
+        _ => {}
+    }
+
+    // Terminal jobs, and jobs whose worker is gone, have nothing more to stream
+    let live = match receiver {
+        Some(receiver) if !status.is_terminal() => stream::unfold(receiver, |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => return Some((event, rx)),
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Job event subscriber lagged, skipped {} events", skipped);
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+        .boxed(),
+        _ => stream::empty().boxed(),
+    };
+
+    let events = stream::iter(initial)
+        .chain(live)
+        .map(|event| Event::default().event(event.name()).json_data(&event));
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
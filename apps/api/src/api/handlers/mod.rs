@@ -1,6 +1,7 @@
 pub mod audio;
 pub mod books;
 pub mod health;
+pub mod jobs;
 pub mod search;
 pub mod summary;
 
@@ -8,11 +9,15 @@ use crate::AppState;
 use axum::{extract::State, Json};
 use serde::{Deserialize, Serialize};
 
-pub use audio::get_audio;
+pub use audio::{get_audio, get_audio_captions, get_audio_timings, list_voices, stream_audio};
 pub use books::get_book;
 pub use health::{health_check, simple_health_check};
+pub use jobs::{get_job, get_job_events};
 pub use search::search_books;
-pub use summary::generate_summary;
+pub use summary::{
+    create_translation, generate_summary, get_structured_summary_schema, get_summary_chapters,
+    get_summary_versions,
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CacheResponse {
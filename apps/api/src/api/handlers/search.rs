@@ -4,10 +4,6 @@ use tokio::time::timeout;
 
 use crate::{
     models::{SearchRequest, SearchResponse},
-    services::{
-        books::{BookAggregatorService, GoogleBooksService, GutenbergService, OpenLibraryService},
-        huggingface::{HuggingFaceClient, NLPService},
-    },
     utils::{
         errors::{AppError, Result},
         validators,
@@ -55,19 +51,10 @@ pub async fn search_books(
 
     tracing::info!("No cached results found, performing new search");
 
-    // Initialize NLP service for query understanding with timeout protection
-    let hf_client = HuggingFaceClient::new(
-        state.http_client.clone(),
-        state.config.hf_api_base_url.clone(),
-        state.config.hf_token.clone(),
-    );
-
-    let nlp_service = NLPService::new(hf_client);
-
     // Try to understand query with timeout and fallback
     let query_intent = timeout(
         Duration::from_secs(5), // Quick timeout for NLP processing
-        nlp_service.understand_query(&payload.query),
+        state.nlp.understand_query(&payload.query),
     )
     .await
     .unwrap_or_else(|_| {
@@ -85,25 +72,12 @@ pub async fn search_books(
         query_intent.search_query
     );
 
-    // Initialize book services
-    let google_books = GoogleBooksService::new(
-        state.http_client.clone(),
-        state.config.google_books_api_key.clone(),
-    );
-
-    let open_library = OpenLibraryService::new(state.http_client.clone());
-
-    let gutenberg = GutenbergService::new(
-        state.http_client.clone(),
-        state.config.gutenberg_api_base_url.clone(),
-    );
-
-    let aggregator = BookAggregatorService::new(google_books, open_library, gutenberg);
-
     // Perform search with timeout protection
     let results = timeout(
         Duration::from_secs(30), // Generous timeout for book search
-        aggregator.search(&query_intent.search_query, payload.limit),
+        state
+            .books
+            .search(&query_intent.search_query, payload.limit),
     )
     .await
     .map_err(|_| AppError::ServiceTimeout("Book search timed out".to_string()))?
@@ -14,7 +14,32 @@ pub fn create_router(state: AppState) -> Router {
         .route("/api/search", post(handlers::search_books))
         .route("/api/books/:id", get(handlers::get_book))
         .route("/api/books/:id/summary", post(handlers::generate_summary))
+        .route(
+            "/api/books/:id/summary/versions",
+            get(handlers::get_summary_versions),
+        )
+        .route("/api/jobs/:id", get(handlers::get_job))
+        .route("/api/jobs/:id/events", get(handlers::get_job_events))
+        .route(
+            "/api/summary/:id/chapters",
+            get(handlers::get_summary_chapters),
+        )
+        .route(
+            "/api/summaries/schema",
+            get(handlers::get_structured_summary_schema),
+        )
+        .route(
+            "/api/summaries/:id/translations",
+            post(handlers::create_translation),
+        )
         .route("/api/summary/:id/audio", get(handlers::get_audio))
+        .route("/api/audio/:id/stream", get(handlers::stream_audio))
+        .route("/api/audio/:id/timings", get(handlers::get_audio_timings))
+        .route(
+            "/api/audio/:id/captions.vtt",
+            get(handlers::get_audio_captions),
+        )
+        .route("/api/voices", get(handlers::list_voices))
         .route("/api/cache/clear", delete(handlers::clear_cache))
         .layer(cors)
         .with_state(state)
@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+
+use crate::utils::{
+    errors::{AppError, Result},
+    text::SentenceRules,
+};
+
+/// Prompt prepended to the text for each summary style
+pub struct StyleInstructions {
+    pub concise: &'static str,
+    pub detailed: &'static str,
+    pub academic: &'static str,
+    pub simple: &'static str,
+}
+
+impl StyleInstructions {
+    pub fn for_style(&self, style: &str) -> &'static str {
+        match style {
+            "detailed" => self.detailed,
+            "academic" => self.academic,
+            "simple" => self.simple,
+            _ => self.concise,
+        }
+    }
+}
+
+/// Section headings of a structured summary's plain-text rendering
+pub struct SectionHeadings {
+    pub overview: &'static str,
+    pub plot: &'static str,
+    pub characters: &'static str,
+    pub themes: &'static str,
+    pub quotes: &'static str,
+    pub reading_level: &'static str,
+}
+
+/// Built-in settings for one language; models can be overridden from the environment
+struct LanguageDefaults {
+    code: &'static str,
+    name: &'static str,
+    /// FLORES-200 code used by translation models such as NLLB
+    translation_code: &'static str,
+    summarization_model: &'static str,
+    /// TTS models tried in order when the language's default voice fails
+    tts_models: &'static [&'static str],
+    sentences: SentenceRules,
+    /// Replacements applied before text is read aloud
+    tts_normalization: &'static [(&'static str, &'static str)],
+    /// Words ignored when ranking sentences for extractive summaries
+    stopwords: &'static [&'static str],
+    instructions: StyleInstructions,
+    headings: SectionHeadings,
+}
+
+static ENGLISH: LanguageDefaults = LanguageDefaults {
+    code: "en",
+    name: "English",
+    translation_code: "eng_Latn",
+    summarization_model: "facebook/bart-large-cnn",
+    tts_models: &[
+        "espnet/kan-bayashi_ljspeech_vits",
+        "facebook/fastspeech2-en-ljspeech",
+    ],
+    sentences: SentenceRules {
+        terminators: &['.', '!', '?'],
+        abbreviations: &[
+            "Mr.", "Mrs.", "Ms.", "Dr.", "St.", "Prof.", "Jr.", "Sr.", "e.g.", "i.e.", "vs.",
+        ],
+    },
+    tts_normalization: &[
+        ("&", "and"),
+        ("@", "at"),
+        ("%", "percent"),
+        ("$", "dollars"),
+        ("€", "euros"),
+        ("£", "pounds"),
+        ("e.g.", "for example"),
+        ("i.e.", "that is"),
+        ("etc.", "and so on"),
+        ("vs.", "versus"),
+        ("Mr.", "Mister"),
+        ("Mrs.", "Missus"),
+        ("Dr.", "Doctor"),
+    ],
+    stopwords: &[
+        "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "been",
+        "but", "by", "can", "could", "did", "do", "for", "from", "had", "has", "have", "he", "her",
+        "him", "his", "i", "if", "in", "into", "is", "it", "its", "me", "my", "no", "not", "of",
+        "on", "one", "or", "our", "she", "so", "that", "the", "their", "them", "then", "there",
+        "they", "this", "to", "up", "was", "we", "were", "what", "which", "who", "with", "you",
+    ],
+    instructions: StyleInstructions {
+        concise: "INSTRUCTION: Create a brief, focused summary that captures only the most essential plot points and main themes. Be direct and concise, highlighting key events and core messages without unnecessary detail:",
+        detailed: "INSTRUCTION: Write a comprehensive, in-depth summary that covers all major themes, character development, plot points, literary devices, and contextual significance. Include specific examples, quotes, and detailed analysis. Aim for thorough coverage with rich descriptions and explanations. Use sophisticated vocabulary and complex sentence structures:",
+        academic: "INSTRUCTION: Compose a formal, scholarly analysis in academic style. Use formal language, analytical frameworks, critical theory perspectives, and structured argumentation. Include discussion of literary merit, historical context, thematic significance, and scholarly interpretations. Maintain objective, analytical tone throughout:",
+        simple: "INSTRUCTION: Write a clear, straightforward summary using simple words and short sentences. Explain everything in an easy-to-understand way, as if writing for someone who is new to reading literature. Avoid complex vocabulary and focus on basic plot and main ideas:",
+    },
+    headings: SectionHeadings {
+        overview: "Overview",
+        plot: "Plot",
+        characters: "Main characters",
+        themes: "Themes",
+        quotes: "Notable quotes",
+        reading_level: "Reading level",
+    },
+};
+
+static GERMAN: LanguageDefaults = LanguageDefaults {
+    code: "de",
+    name: "German",
+    translation_code: "deu_Latn",
+    summarization_model: "mrm8488/bert2bert_shared-german-finetuned-summarization",
+    tts_models: &["facebook/mms-tts-deu"],
+    sentences: SentenceRules {
+        terminators: &['.', '!', '?'],
+        abbreviations: &[
+            "z.B.", "d.h.", "u.a.", "usw.", "bzw.", "ca.", "Dr.", "Prof.", "Hr.", "Fr.", "Nr.",
+            "vgl.", "S.", "Jh.",
+        ],
+    },
+    tts_normalization: &[
+        ("&", "und"),
+        ("%", "Prozent"),
+        ("€", "Euro"),
+        ("$", "Dollar"),
+        ("£", "Pfund"),
+        ("z.B.", "zum Beispiel"),
+        ("d.h.", "das heißt"),
+        ("u.a.", "unter anderem"),
+        ("usw.", "und so weiter"),
+        ("bzw.", "beziehungsweise"),
+        ("ca.", "circa"),
+        ("Nr.", "Nummer"),
+        ("Dr.", "Doktor"),
+        ("Prof.", "Professor"),
+    ],
+    stopwords: &[
+        "aber", "als", "am", "an", "auch", "auf", "aus", "bei", "bin", "bis", "da", "das", "dass",
+        "dem", "den", "der", "des", "die", "doch", "du", "ein", "eine", "einem", "einen", "einer",
+        "er", "es", "für", "hat", "hatte", "ich", "ihm", "ihn", "ihr", "im", "in", "ist", "mit",
+        "nach", "nicht", "noch", "nur", "oder", "sich", "sie", "sind", "so", "um", "und", "von",
+        "vor", "war", "was", "wenn", "wie", "wir", "wird", "zu", "zum", "zur",
+    ],
+    instructions: StyleInstructions {
+        concise: "INSTRUCTION: Schreibe eine kurze, fokussierte Zusammenfassung auf Deutsch, die nur die wichtigsten Handlungspunkte und Hauptthemen erfasst. Sei direkt und knapp und hebe die zentralen Ereignisse ohne unnötige Details hervor:",
+        detailed: "INSTRUCTION: Schreibe eine umfassende, ausführliche Zusammenfassung auf Deutsch, die alle wichtigen Themen, die Entwicklung der Figuren, die Handlung, literarische Mittel und den historischen Kontext behandelt. Nenne konkrete Beispiele und Zitate und erkläre sie gründlich:",
+        academic: "INSTRUCTION: Verfasse eine formale, wissenschaftliche Analyse auf Deutsch. Verwende eine sachliche Sprache und eine klare Argumentation und gehe auf literarischen Wert, historischen Kontext, thematische Bedeutung und wissenschaftliche Deutungen ein:",
+        simple: "INSTRUCTION: Schreibe eine klare, leicht verständliche Zusammenfassung auf Deutsch mit einfachen Wörtern und kurzen Sätzen, als ob du für jemanden schreibst, der gerade mit dem Lesen von Literatur beginnt. Konzentriere dich auf die Handlung und die Hauptideen:",
+    },
+    headings: SectionHeadings {
+        overview: "Überblick",
+        plot: "Handlung",
+        characters: "Hauptfiguren",
+        themes: "Themen",
+        quotes: "Bemerkenswerte Zitate",
+        reading_level: "Leseniveau",
+    },
+};
+
+static TAMIL: LanguageDefaults = LanguageDefaults {
+    code: "ta",
+    name: "Tamil",
+    translation_code: "tam_Taml",
+    summarization_model: "csebuetnlp/mT5_multilingual_XLSum",
+    tts_models: &["facebook/mms-tts-tam"],
+    sentences: SentenceRules {
+        terminators: &['.', '!', '?'],
+        abbreviations: &["Dr.", "Mr.", "Mrs."],
+    },
+    tts_normalization: &[
+        ("&", "மற்றும்"),
+        ("%", "சதவீதம்"),
+        ("₹", "ரூபாய்"),
+        ("$", "டாலர்"),
+        ("€", "யூரோ"),
+    ],
+    stopwords: &[
+        "அது", "இது", "அந்த", "இந்த", "ஒரு", "மற்றும்", "என்று", "என", "அவர்", "அவள்", "அவன்",
+        "அவர்கள்", "நான்", "நாம்", "நீ", "அல்லது", "ஆனால்", "மேலும்", "உள்ள", "இருந்த", "போது",
+        "பின்", "முன்", "கூட", "தான்", "இல்லை",
+    ],
+    instructions: StyleInstructions {
+        concise: "INSTRUCTION: மிக முக்கியமான கதை நிகழ்வுகளையும் முக்கிய கருப்பொருள்களையும் மட்டும் உள்ளடக்கிய சுருக்கமான தமிழ்ச் சுருக்கத்தை எழுதுங்கள்:",
+        detailed: "INSTRUCTION: முக்கிய கருப்பொருள்கள், கதாபாத்திர வளர்ச்சி, கதை நிகழ்வுகள் மற்றும் இலக்கியச் சிறப்புகள் அனைத்தையும் உள்ளடக்கிய விரிவான தமிழ்ச் சுருக்கத்தை எழுதுங்கள்:",
+        academic: "INSTRUCTION: வரலாற்றுப் பின்னணி, கருப்பொருள் முக்கியத்துவம் மற்றும் இலக்கியத் தகுதி குறித்த முறையான, ஆய்வு நடையிலான தமிழ்ப் பகுப்பாய்வை எழுதுங்கள்:",
+        simple: "INSTRUCTION: எளிய சொற்களையும் சிறிய வாக்கியங்களையும் பயன்படுத்தி, அடிப்படைக் கதையையும் முக்கியக் கருத்துகளையும் விளக்கும் தெளிவான தமிழ்ச் சுருக்கத்தை எழுதுங்கள்:",
+    },
+    headings: SectionHeadings {
+        overview: "கண்ணோட்டம்",
+        plot: "கதைப் போக்கு",
+        characters: "முக்கிய கதாபாத்திரங்கள்",
+        themes: "கருப்பொருள்கள்",
+        quotes: "குறிப்பிடத்தக்க மேற்கோள்கள்",
+        reading_level: "வாசிப்பு நிலை",
+    },
+};
+
+static BUILTIN_LANGUAGES: [&LanguageDefaults; 3] = [&ENGLISH, &GERMAN, &TAMIL];
+
+/// Codes of every language with built-in settings
+pub fn builtin_codes() -> Vec<&'static str> {
+    BUILTIN_LANGUAGES
+        .iter()
+        .map(|language| language.code)
+        .collect()
+}
+
+/// Everything the pipeline needs to summarize and voice one language
+pub struct LanguageProfile {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub translation_code: &'static str,
+    pub summarization_model: String,
+    pub tts_models: Vec<String>,
+    pub sentences: &'static SentenceRules,
+    pub tts_normalization: &'static [(&'static str, &'static str)],
+    pub stopwords: &'static [&'static str],
+    pub instructions: &'static StyleInstructions,
+    pub headings: &'static SectionHeadings,
+}
+
+impl LanguageProfile {
+    fn from_defaults(defaults: &'static LanguageDefaults) -> Self {
+        Self {
+            code: defaults.code,
+            name: defaults.name,
+            translation_code: defaults.translation_code,
+            summarization_model: defaults.summarization_model.to_string(),
+            tts_models: defaults.tts_models.iter().map(|m| m.to_string()).collect(),
+            sentences: &defaults.sentences,
+            tts_normalization: defaults.tts_normalization,
+            stopwords: defaults.stopwords,
+            instructions: &defaults.instructions,
+            headings: &defaults.headings,
+        }
+    }
+}
+
+/// The languages this deployment supports, in configured order
+pub struct Languages {
+    profiles: Vec<LanguageProfile>,
+}
+
+impl Languages {
+    /// Build the enabled languages, applying per-language model overrides.
+    ///
+    /// `enabled` must only name built-in languages; `Settings` checks this on load.
+    pub fn new(
+        enabled: &[String],
+        summarization_models: &HashMap<String, String>,
+        tts_models: &HashMap<String, Vec<String>>,
+    ) -> Self {
+        let profiles = enabled
+            .iter()
+            .filter_map(|code| BUILTIN_LANGUAGES.iter().find(|l| l.code == code))
+            .map(|defaults| {
+                let mut profile = LanguageProfile::from_defaults(defaults);
+                if let Some(model) = summarization_models.get(profile.code) {
+                    profile.summarization_model = model.clone();
+                }
+                if let Some(models) = tts_models.get(profile.code) {
+                    profile.tts_models = models.clone();
+                }
+                profile
+            })
+            .collect();
+
+        Self { profiles }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &LanguageProfile> {
+        self.profiles.iter()
+    }
+
+    pub fn codes(&self) -> Vec<&'static str> {
+        self.profiles.iter().map(|profile| profile.code).collect()
+    }
+
+    pub fn get(&self, code: &str) -> Option<&LanguageProfile> {
+        self.profiles.iter().find(|profile| profile.code == code)
+    }
+
+    /// The profile for `code`, or an `InvalidInput` error listing the supported languages
+    pub fn resolve(&self, code: &str) -> Result<&LanguageProfile> {
+        self.get(code).ok_or_else(|| {
+            AppError::InvalidInput(format!(
+                "Unsupported language: {}. Supported: {}",
+                code,
+                self.codes().join(", ")
+            ))
+        })
+    }
+
+    /// Sentence rules for `code`, even when the language is no longer enabled, so text
+    /// stored earlier can still be split. Unknown languages use the English rules.
+    pub fn sentence_rules(&self, code: &str) -> &'static SentenceRules {
+        BUILTIN_LANGUAGES
+            .iter()
+            .find(|language| language.code == code)
+            .map_or(&ENGLISH.sentences, |language| &language.sentences)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::text;
+
+    fn enabled(codes: &[&str]) -> Vec<String> {
+        codes.iter().map(|code| code.to_string()).collect()
+    }
+
+    fn all() -> Languages {
+        Languages::new(&enabled(&builtin_codes()), &HashMap::new(), &HashMap::new())
+    }
+
+    #[test]
+    fn each_language_resolves_to_its_models_and_sentence_rules() {
+        let languages = all();
+        assert_eq!(languages.codes(), ["en", "de", "ta"]);
+
+        for defaults in BUILTIN_LANGUAGES {
+            let profile = languages.resolve(defaults.code).unwrap();
+            assert_eq!(profile.name, defaults.name);
+            assert_eq!(profile.summarization_model, defaults.summarization_model);
+            assert_eq!(profile.tts_models, defaults.tts_models);
+            assert!(std::ptr::eq(profile.sentences, &defaults.sentences));
+            assert!(std::ptr::eq(
+                languages.sentence_rules(defaults.code),
+                &defaults.sentences
+            ));
+        }
+    }
+
+    #[test]
+    fn sentence_rules_follow_the_language() {
+        let languages = all();
+        let german = "Das gilt z.B. für Bücher. Noch ein Satz.";
+        assert_eq!(
+            text::sentence_spans(german, languages.resolve("de").unwrap().sentences).len(),
+            2
+        );
+        assert_eq!(
+            text::sentence_spans(german, languages.resolve("en").unwrap().sentences).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn unknown_and_disabled_codes_are_rejected() {
+        let languages = Languages::new(&enabled(&["en", "xx"]), &HashMap::new(), &HashMap::new());
+        assert_eq!(languages.codes(), ["en"]);
+
+        for code in ["xx", "de", "", "EN"] {
+            match languages.resolve(code) {
+                Err(AppError::InvalidInput(message)) => assert!(message.contains("Supported: en")),
+                _ => panic!("{:?} should be rejected", code),
+            }
+        }
+
+        // Stored text in a disabled or unknown language can still be split into sentences
+        assert!(std::ptr::eq(
+            languages.sentence_rules("de"),
+            &GERMAN.sentences
+        ));
+        assert!(std::ptr::eq(
+            languages.sentence_rules("xx"),
+            &ENGLISH.sentences
+        ));
+    }
+
+    #[test]
+    fn configured_models_override_the_defaults() {
+        let summarization = HashMap::from([("de".to_string(), "custom/summarizer".to_string())]);
+        let tts = HashMap::from([("en".to_string(), vec!["custom/tts".to_string()])]);
+        let languages = Languages::new(&enabled(&["de", "en"]), &summarization, &tts);
+
+        assert_eq!(languages.codes(), ["de", "en"]);
+        let german = languages.resolve("de").unwrap();
+        assert_eq!(german.summarization_model, "custom/summarizer");
+        assert_eq!(german.tts_models, GERMAN.tts_models);
+        let english = languages.resolve("en").unwrap();
+        assert_eq!(english.summarization_model, ENGLISH.summarization_model);
+        assert_eq!(english.tts_models, ["custom/tts"]);
+    }
+}
@@ -1,3 +1,5 @@
+mod languages;
 mod settings;
 
+pub use languages::{LanguageProfile, Languages, SectionHeadings};
 pub use settings::Settings;
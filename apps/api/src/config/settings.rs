@@ -1,6 +1,8 @@
 use anyhow::{Context, Result};
 use serde::Deserialize;
-use std::env;
+use std::{collections::HashMap, env};
+
+use crate::config::languages;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Settings {
@@ -15,16 +17,113 @@ pub struct Settings {
     #[serde(default = "default_pool_size")]
     pub database_pool_size: u32,
 
+    /// Connections reserved for advisory locks, one per lock held
+    #[serde(default = "default_lock_pool_size")]
+    pub database_lock_pool_size: u32,
+
+    #[serde(default = "default_inference_backend")]
+    pub inference_backend: String,
+
+    #[serde(default)]
     pub hf_token: String,
 
     #[serde(default = "default_hf_base_url")]
     pub hf_api_base_url: String,
 
+    #[serde(default = "default_openai_base_url")]
+    pub openai_api_base_url: String,
+
+    pub openai_api_key: Option<String>,
+
+    #[serde(default = "default_openai_chat_model")]
+    pub openai_chat_model: String,
+
+    #[serde(default = "default_openai_tts_model")]
+    pub openai_tts_model: String,
+
+    #[serde(default = "default_openai_tts_voice")]
+    pub openai_tts_voice: String,
+
+    /// Backend that serves translations; the inference backend unless set
+    #[serde(default = "default_inference_backend")]
+    pub translation_backend: String,
+
+    #[serde(default = "default_translation_model")]
+    pub translation_model: String,
+
+    /// Instruction-following model that writes structured summaries as JSON
+    #[serde(default = "default_structured_summary_model")]
+    pub structured_summary_model: String,
+
+    /// What to do when book content is not in the requested summary language:
+    /// `translate` the summary or `reject` the job
+    #[serde(default = "default_language_mismatch")]
+    pub language_mismatch: String,
+
     pub google_books_api_key: Option<String>,
 
+    #[serde(default = "default_google_books_base_url")]
+    pub google_books_api_base_url: String,
+
+    #[serde(default = "default_open_library_base_url")]
+    pub open_library_base_url: String,
+
+    #[serde(default = "default_open_library_covers_base_url")]
+    pub open_library_covers_base_url: String,
+
+    #[serde(default = "default_internet_archive_base_url")]
+    pub internet_archive_base_url: String,
+
     #[serde(default = "default_gutenberg_base_url")]
     pub gutenberg_api_base_url: String,
 
+    #[serde(default = "default_gutenberg_content_base_url")]
+    pub gutenberg_content_base_url: String,
+
+    #[serde(default)]
+    pub disabled_book_providers: Vec<String>,
+
+    #[serde(default = "default_supported_languages")]
+    pub supported_languages: Vec<String>,
+
+    /// Summarization model per language code, overriding the built-in choice
+    #[serde(default)]
+    pub summarization_models: HashMap<String, String>,
+
+    /// Fallback TTS models per language code, in the order they are tried
+    #[serde(default)]
+    pub tts_models: HashMap<String, Vec<String>>,
+
+    #[serde(default = "default_summary_workers")]
+    pub summary_workers: usize,
+
+    #[serde(default = "default_summary_chunk_concurrency")]
+    pub summary_chunk_concurrency: usize,
+
+    #[serde(default = "default_summary_timeout")]
+    pub summary_timeout_seconds: u64,
+
+    /// Words on one page of a summary, turning `max_pages` into a length budget
+    #[serde(default = "default_words_per_page")]
+    pub words_per_page: usize,
+
+    #[serde(default = "default_blob_store")]
+    pub blob_store: String,
+
+    #[serde(default = "default_blob_store_path")]
+    pub blob_store_path: String,
+
+    pub s3_endpoint: Option<String>,
+
+    pub s3_bucket: Option<String>,
+
+    #[serde(default = "default_s3_region")]
+    pub s3_region: String,
+
+    pub s3_access_key_id: Option<String>,
+
+    pub s3_secret_access_key: Option<String>,
+
     #[serde(default = "default_cache_ttl")]
     pub cache_ttl_seconds: u64,
 
@@ -47,14 +146,112 @@ fn default_pool_size() -> u32 {
     5
 }
 
+fn default_lock_pool_size() -> u32 {
+    10
+}
+
+fn default_inference_backend() -> String {
+    "huggingface".to_string()
+}
+
 fn default_hf_base_url() -> String {
     "https://api-inference.huggingface.co".to_string()
 }
 
+fn default_openai_base_url() -> String {
+    "https://api.openai.com".to_string()
+}
+
+fn default_openai_chat_model() -> String {
+    "gpt-4o-mini".to_string()
+}
+
+fn default_openai_tts_model() -> String {
+    "tts-1".to_string()
+}
+
+fn default_openai_tts_voice() -> String {
+    "alloy".to_string()
+}
+
+fn default_translation_model() -> String {
+    "facebook/nllb-200-distilled-600M".to_string()
+}
+
+fn default_structured_summary_model() -> String {
+    "mistralai/Mistral-7B-Instruct-v0.2".to_string()
+}
+
+fn default_language_mismatch() -> String {
+    "translate".to_string()
+}
+
+fn default_google_books_base_url() -> String {
+    "https://www.googleapis.com/books/v1".to_string()
+}
+
+fn default_open_library_base_url() -> String {
+    "https://openlibrary.org".to_string()
+}
+
+fn default_open_library_covers_base_url() -> String {
+    "https://covers.openlibrary.org".to_string()
+}
+
+fn default_internet_archive_base_url() -> String {
+    "https://archive.org".to_string()
+}
+
 fn default_gutenberg_base_url() -> String {
     "https://gutendex.com".to_string()
 }
 
+fn default_gutenberg_content_base_url() -> String {
+    "https://www.gutenberg.org".to_string()
+}
+
+fn default_supported_languages() -> Vec<String> {
+    vec!["en".to_string(), "de".to_string(), "ta".to_string()]
+}
+
+/// Parse `lang=value` pairs separated by commas, e.g. `de=model-a,ta=model-b`
+fn parse_language_pairs(value: &str) -> Vec<(String, String)> {
+    value
+        .split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(lang, value)| (lang.trim().to_lowercase(), value.trim().to_string()))
+        .filter(|(lang, value)| !lang.is_empty() && !value.is_empty())
+        .collect()
+}
+
+fn default_summary_workers() -> usize {
+    2
+}
+
+fn default_summary_chunk_concurrency() -> usize {
+    4
+}
+
+fn default_summary_timeout() -> u64 {
+    900
+}
+
+fn default_words_per_page() -> usize {
+    300
+}
+
+fn default_blob_store() -> String {
+    "filesystem".to_string()
+}
+
+fn default_blob_store_path() -> String {
+    "./data/blobs".to_string()
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
 fn default_cache_ttl() -> u64 {
     3600
 }
@@ -88,19 +285,209 @@ impl Settings {
             .and_then(|p| p.parse().ok())
             .unwrap_or_else(default_pool_size);
 
-        let hf_token = env::var("HF_TOKEN")
-            .or_else(|_| env::var("APP_HUGGINGFACE_API_KEY"))
-            .context("HF_TOKEN or APP_HUGGINGFACE_API_KEY must be set")?;
+        let database_lock_pool_size = env::var("DATABASE_LOCK_POOL_SIZE")
+            .ok()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or_else(default_lock_pool_size);
+
+        let inference_backend = env::var("INFERENCE_BACKEND")
+            .map(|b| b.trim().to_lowercase())
+            .unwrap_or_else(|_| default_inference_backend());
+
+        if !["huggingface", "openai"].contains(&inference_backend.as_str()) {
+            anyhow::bail!(
+                "Unknown INFERENCE_BACKEND '{}'. Supported: huggingface, openai",
+                inference_backend
+            );
+        }
+
+        let translation_backend = env::var("TRANSLATION_BACKEND")
+            .map(|b| b.trim().to_lowercase())
+            .unwrap_or_else(|_| inference_backend.clone());
+
+        if !["huggingface", "openai"].contains(&translation_backend.as_str()) {
+            anyhow::bail!(
+                "Unknown TRANSLATION_BACKEND '{}'. Supported: huggingface, openai",
+                translation_backend
+            );
+        }
+
+        let translation_model =
+            env::var("TRANSLATION_MODEL").unwrap_or_else(|_| default_translation_model());
+
+        let structured_summary_model = env::var("STRUCTURED_SUMMARY_MODEL")
+            .unwrap_or_else(|_| default_structured_summary_model());
+
+        let language_mismatch = env::var("LANGUAGE_MISMATCH")
+            .map(|m| m.trim().to_lowercase())
+            .unwrap_or_else(|_| default_language_mismatch());
+
+        if !["translate", "reject"].contains(&language_mismatch.as_str()) {
+            anyhow::bail!(
+                "Unknown LANGUAGE_MISMATCH '{}'. Supported: translate, reject",
+                language_mismatch
+            );
+        }
+
+        // The HuggingFace token is only mandatory when HuggingFace serves inference
+        let hf_token = env::var("HF_TOKEN").or_else(|_| env::var("APP_HUGGINGFACE_API_KEY"));
+        let hf_token = if inference_backend == "huggingface" || translation_backend == "huggingface"
+        {
+            hf_token.context("HF_TOKEN or APP_HUGGINGFACE_API_KEY must be set")?
+        } else {
+            hf_token.unwrap_or_default()
+        };
 
         let hf_api_base_url = env::var("HF_API_BASE_URL")
             .or_else(|_| env::var("APP_HUGGINGFACE_API_BASE_URL"))
             .unwrap_or_else(|_| default_hf_base_url());
 
+        let openai_api_base_url =
+            env::var("OPENAI_API_BASE_URL").unwrap_or_else(|_| default_openai_base_url());
+
+        let openai_api_key = env::var("OPENAI_API_KEY").ok();
+
+        let openai_chat_model =
+            env::var("OPENAI_CHAT_MODEL").unwrap_or_else(|_| default_openai_chat_model());
+
+        let openai_tts_model =
+            env::var("OPENAI_TTS_MODEL").unwrap_or_else(|_| default_openai_tts_model());
+
+        let openai_tts_voice =
+            env::var("OPENAI_TTS_VOICE").unwrap_or_else(|_| default_openai_tts_voice());
+
         let google_books_api_key = env::var("GOOGLE_BOOKS_API_KEY").ok();
 
+        let google_books_api_base_url = env::var("GOOGLE_BOOKS_API_BASE_URL")
+            .unwrap_or_else(|_| default_google_books_base_url());
+
+        let open_library_base_url =
+            env::var("OPEN_LIBRARY_BASE_URL").unwrap_or_else(|_| default_open_library_base_url());
+
+        let open_library_covers_base_url = env::var("OPEN_LIBRARY_COVERS_BASE_URL")
+            .unwrap_or_else(|_| default_open_library_covers_base_url());
+
+        let internet_archive_base_url = env::var("INTERNET_ARCHIVE_BASE_URL")
+            .unwrap_or_else(|_| default_internet_archive_base_url());
+
         let gutenberg_api_base_url =
             env::var("GUTENBERG_API_BASE_URL").unwrap_or_else(|_| default_gutenberg_base_url());
 
+        let gutenberg_content_base_url = env::var("GUTENBERG_CONTENT_BASE_URL")
+            .unwrap_or_else(|_| default_gutenberg_content_base_url());
+
+        let disabled_book_providers = env::var("DISABLED_BOOK_PROVIDERS")
+            .ok()
+            .map(|p| {
+                p.split(',')
+                    .map(|s| s.trim().to_lowercase())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let supported_languages: Vec<String> = env::var("SUPPORTED_LANGUAGES")
+            .ok()
+            .map(|l| {
+                l.split(',')
+                    .map(|s| s.trim().to_lowercase())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .filter(|l: &Vec<String>| !l.is_empty())
+            .unwrap_or_else(default_supported_languages);
+
+        let builtin = languages::builtin_codes();
+        if let Some(unknown) = supported_languages
+            .iter()
+            .find(|l| !builtin.contains(&l.as_str()))
+        {
+            anyhow::bail!(
+                "Unknown language '{}' in SUPPORTED_LANGUAGES. Supported: {}",
+                unknown,
+                builtin.join(", ")
+            );
+        }
+
+        let summarization_models: HashMap<String, String> = env::var("SUMMARIZATION_MODELS")
+            .map(|m| parse_language_pairs(&m).into_iter().collect())
+            .unwrap_or_default();
+
+        let mut tts_models: HashMap<String, Vec<String>> = HashMap::new();
+        if let Ok(models) = env::var("TTS_MODELS") {
+            for (lang, model) in parse_language_pairs(&models) {
+                tts_models.entry(lang).or_default().push(model);
+            }
+        }
+
+        let summary_workers = env::var("SUMMARY_WORKERS")
+            .ok()
+            .and_then(|w| w.parse().ok())
+            .unwrap_or_else(default_summary_workers);
+
+        // Every running summary job holds a lock, and requests need room to lock alongside them
+        if database_lock_pool_size as usize <= summary_workers {
+            anyhow::bail!(
+                "DATABASE_LOCK_POOL_SIZE ({}) must be greater than SUMMARY_WORKERS ({})",
+                database_lock_pool_size,
+                summary_workers
+            );
+        }
+
+        let summary_chunk_concurrency = env::var("SUMMARY_CHUNK_CONCURRENCY")
+            .ok()
+            .and_then(|c| c.parse().ok())
+            .unwrap_or_else(default_summary_chunk_concurrency);
+
+        let summary_timeout_seconds = env::var("SUMMARY_TIMEOUT_SECONDS")
+            .ok()
+            .and_then(|t| t.parse().ok())
+            .unwrap_or_else(default_summary_timeout);
+
+        let words_per_page = env::var("WORDS_PER_PAGE")
+            .ok()
+            .and_then(|w| w.parse().ok())
+            .unwrap_or_else(default_words_per_page);
+
+        if words_per_page == 0 {
+            anyhow::bail!("WORDS_PER_PAGE must be greater than 0");
+        }
+
+        let blob_store = env::var("BLOB_STORE")
+            .map(|b| b.trim().to_lowercase())
+            .unwrap_or_else(|_| default_blob_store());
+
+        if !["filesystem", "s3"].contains(&blob_store.as_str()) {
+            anyhow::bail!(
+                "Unknown BLOB_STORE '{}'. Supported: filesystem, s3",
+                blob_store
+            );
+        }
+
+        let blob_store_path =
+            env::var("BLOB_STORE_PATH").unwrap_or_else(|_| default_blob_store_path());
+
+        let s3_endpoint = env::var("S3_ENDPOINT")
+            .ok()
+            .filter(|e| !e.trim().is_empty());
+        let s3_bucket = env::var("S3_BUCKET").ok();
+        let s3_region = env::var("S3_REGION").unwrap_or_else(|_| default_s3_region());
+        let s3_access_key_id = env::var("S3_ACCESS_KEY_ID").ok();
+        let s3_secret_access_key = env::var("S3_SECRET_ACCESS_KEY").ok();
+
+        // Bucket and credentials are only mandatory when S3 holds the blobs
+        if blob_store == "s3" {
+            s3_bucket
+                .as_ref()
+                .context("S3_BUCKET must be set when BLOB_STORE=s3")?;
+            s3_access_key_id
+                .as_ref()
+                .context("S3_ACCESS_KEY_ID must be set when BLOB_STORE=s3")?;
+            s3_secret_access_key
+                .as_ref()
+                .context("S3_SECRET_ACCESS_KEY must be set when BLOB_STORE=s3")?;
+        }
+
         let cache_ttl_seconds = env::var("CACHE_TTL_SECONDS")
             .ok()
             .and_then(|t| t.parse().ok())
@@ -121,10 +508,41 @@ impl Settings {
             environment,
             database_url,
             database_pool_size,
+            database_lock_pool_size,
+            inference_backend,
             hf_token,
             hf_api_base_url,
+            openai_api_base_url,
+            openai_api_key,
+            openai_chat_model,
+            openai_tts_model,
+            openai_tts_voice,
+            translation_backend,
+            translation_model,
+            structured_summary_model,
+            language_mismatch,
             google_books_api_key,
+            google_books_api_base_url,
+            open_library_base_url,
+            open_library_covers_base_url,
+            internet_archive_base_url,
             gutenberg_api_base_url,
+            gutenberg_content_base_url,
+            disabled_book_providers,
+            supported_languages,
+            summarization_models,
+            tts_models,
+            summary_workers,
+            summary_chunk_concurrency,
+            summary_timeout_seconds,
+            words_per_page,
+            blob_store,
+            blob_store_path,
+            s3_endpoint,
+            s3_bucket,
+            s3_region,
+            s3_access_key_id,
+            s3_secret_access_key,
             cache_ttl_seconds,
             cache_max_capacity,
             allowed_origins,
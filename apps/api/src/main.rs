@@ -5,22 +5,154 @@ mod models;
 mod services;
 mod utils;
 
-use std::net::SocketAddr;
-use tower_http::{compression::CompressionLayer, trace::TraceLayer};
+use std::{net::SocketAddr, sync::Arc};
+use tower_http::{
+    compression::{
+        predicate::{NotForContentType, Predicate},
+        CompressionLayer, DefaultPredicate,
+    },
+    trace::TraceLayer,
+};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::{
     api::routes::create_router,
-    config::Settings,
-    services::{cache::CacheService, storage::DatabaseService},
+    config::{Languages, Settings},
+    models::AudioResponse,
+    services::{
+        books::{
+            BookAggregatorService, BookProvider, BookProviderRegistry, GoogleBooksService,
+            GutenbergService, OpenLibraryService,
+        },
+        cache::CacheService,
+        coalesce::SingleFlight,
+        huggingface::{
+            NLPService, StructuredSummaryService, SummarizerService, TTSService, TranslatorService,
+        },
+        inference,
+        jobs::{spawn_audio_retry_worker, spawn_summary_workers, SummaryJobQueue},
+        storage::{backfill_inline_audio, create_blob_store, BlobStore, DatabaseService},
+    },
 };
 
 #[derive(Clone)]
 pub struct AppState {
     pub config: Settings,
+    pub languages: Arc<Languages>,
     pub db: DatabaseService,
     pub cache: CacheService,
+    pub blobs: Arc<dyn BlobStore>,
     pub http_client: reqwest::Client,
+    pub books: Arc<BookAggregatorService>,
+    pub nlp: Arc<NLPService>,
+    pub summarizer: Arc<SummarizerService>,
+    pub tts: Arc<TTSService>,
+    pub translator: Arc<TranslatorService>,
+    pub structured: Arc<StructuredSummaryService>,
+    pub summary_jobs: SummaryJobQueue,
+    pub audio_requests: SingleFlight<AudioResponse>,
+}
+
+impl AppState {
+    /// Build the long-lived services shared by every request
+    pub fn new(
+        config: Settings,
+        db: DatabaseService,
+        cache: CacheService,
+        http_client: reqwest::Client,
+        summary_jobs: SummaryJobQueue,
+    ) -> Self {
+        let providers: Vec<Arc<dyn BookProvider>> = vec![
+            Arc::new(GoogleBooksService::new(
+                http_client.clone(),
+                config.google_books_api_base_url.clone(),
+                config.google_books_api_key.clone(),
+            )),
+            Arc::new(OpenLibraryService::new(
+                http_client.clone(),
+                config.open_library_base_url.clone(),
+                config.open_library_covers_base_url.clone(),
+                config.internet_archive_base_url.clone(),
+            )),
+            Arc::new(GutenbergService::new(
+                http_client.clone(),
+                config.gutenberg_api_base_url.clone(),
+                config.gutenberg_content_base_url.clone(),
+            )),
+        ];
+
+        let registry = providers
+            .into_iter()
+            .filter(|provider| {
+                let disabled = config
+                    .disabled_book_providers
+                    .iter()
+                    .any(|p| p == provider.id_prefix());
+                if disabled {
+                    tracing::info!("Book provider '{}' disabled", provider.id_prefix());
+                }
+                !disabled
+            })
+            .fold(BookProviderRegistry::new(), |registry, provider| {
+                registry.register(provider)
+            });
+
+        let backend =
+            inference::create_backend(&config.inference_backend, &config, http_client.clone());
+        tracing::info!("Using '{}' inference backend", backend.name());
+
+        let translation_backend = if config.translation_backend == config.inference_backend {
+            backend.clone()
+        } else {
+            inference::create_backend(&config.translation_backend, &config, http_client.clone())
+        };
+        tracing::info!(
+            "Using '{}' translation backend with model {}",
+            translation_backend.name(),
+            config.translation_model
+        );
+
+        let blobs = create_blob_store(&config, http_client.clone());
+        tracing::info!("Using '{}' blob store", blobs.name());
+
+        let languages = Arc::new(Languages::new(
+            &config.supported_languages,
+            &config.summarization_models,
+            &config.tts_models,
+        ));
+        tracing::info!("Supported languages: {}", languages.codes().join(", "));
+
+        Self {
+            books: Arc::new(BookAggregatorService::new(registry)),
+            nlp: Arc::new(NLPService::new(backend.clone())),
+            summarizer: Arc::new(SummarizerService::new(
+                backend.clone(),
+                db.clone(),
+                languages.clone(),
+                config.summary_chunk_concurrency,
+                config.words_per_page,
+            )),
+            structured: Arc::new(StructuredSummaryService::new(
+                backend.clone(),
+                config.structured_summary_model.clone(),
+                languages.clone(),
+            )),
+            tts: Arc::new(TTSService::new(backend, languages.clone())),
+            translator: Arc::new(TranslatorService::new(
+                translation_backend,
+                config.translation_model.clone(),
+                languages.clone(),
+            )),
+            languages,
+            summary_jobs,
+            audio_requests: SingleFlight::new(),
+            blobs,
+            config,
+            db,
+            cache,
+            http_client,
+        }
+    }
 }
 
 #[tokio::main]
@@ -78,16 +210,45 @@ async fn main() -> anyhow::Result<()> {
         .build()?;
 
     // Create application state
-    let state = AppState {
-        config: config.clone(),
-        db,
-        cache,
-        http_client,
-    };
+    let (summary_jobs, job_receiver) = SummaryJobQueue::new();
+    let state = AppState::new(config.clone(), db, cache, http_client, summary_jobs);
+    tracing::info!("Shared services initialized");
+
+    // Start background summary workers and pick up jobs left over from a previous run
+    spawn_summary_workers(state.clone(), job_receiver, config.summary_workers);
+    tracing::info!("Started {} summary workers", config.summary_workers.max(1));
+
+    match state.db.get_unfinished_summary_jobs().await {
+        Ok(jobs) => {
+            if !jobs.is_empty() {
+                tracing::info!("Resuming {} unfinished summary jobs", jobs.len());
+            }
+            for job in jobs {
+                state.summary_jobs.enqueue(job.id)?;
+            }
+        }
+        Err(e) => tracing::warn!("Failed to load unfinished summary jobs: {}", e),
+    }
+
+    // Keep retrying real speech for audio that fell back to the placeholder tone
+    spawn_audio_retry_worker(state.clone());
+
+    // Copy audio stored inline by earlier versions into the blob store
+    let backfill_state = state.clone();
+    tokio::spawn(async move {
+        match backfill_inline_audio(&backfill_state.db, backfill_state.blobs.as_ref()).await {
+            Ok(0) => {}
+            Ok(moved) => tracing::info!("Moved {} inline audio files to the blob store", moved),
+            Err(e) => tracing::warn!("Failed to move inline audio to the blob store: {}", e),
+        }
+    });
 
     // Build application router
+    // Audio is already compact and must keep its byte offsets intact for range requests
+    let compression = CompressionLayer::new()
+        .compress_when(DefaultPredicate::new().and(NotForContentType::const_new("audio/")));
     let app = create_router(state)
-        .layer(CompressionLayer::new())
+        .layer(compression)
         .layer(TraceLayer::new_for_http());
 
     // Start server
@@ -134,7 +295,13 @@ async fn initialize_database(config: &Settings) -> anyhow::Result<DatabaseServic
             max_retries
         );
 
-        match DatabaseService::new(&config.database_url, config.database_pool_size).await {
+        match DatabaseService::new(
+            &config.database_url,
+            config.database_pool_size,
+            config.database_lock_pool_size,
+        )
+        .await
+        {
             Ok(db) => {
                 tracing::info!(
                     "Database connection established successfully on attempt {}",
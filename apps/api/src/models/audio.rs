@@ -1,9 +1,9 @@
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AudioResponse {
     pub id: Uuid,
     pub summary_id: Uuid,
@@ -11,6 +11,12 @@ pub struct AudioResponse {
     pub voice_type: String,
     pub duration_ms: Option<i32>,
     pub file_size_kb: Option<i32>,
+    pub content_type: String,
+    pub sample_rate: Option<i32>,
+    pub channels: Option<i16>,
+    pub bit_depth: Option<i16>,
+    /// The tone played when speech synthesis failed; real speech is retried in the background
+    pub is_placeholder: bool,
     pub audio_url: String,
     pub created_at: DateTime<Utc>,
 }
@@ -21,22 +27,50 @@ pub struct AudioFile {
     pub summary_id: Uuid,
     pub language: String,
     pub voice_type: String,
-    pub file_url: String,
+    /// Base64 data URL for audio stored before the blob store existed
+    pub file_url: Option<String>,
+    pub blob_key: Option<String>,
+    pub content_type: String,
     pub duration_ms: Option<i32>,
     pub file_size_kb: Option<i32>,
+    pub sample_rate: Option<i32>,
+    pub channels: Option<i16>,
+    pub bit_depth: Option<i16>,
+    pub is_placeholder: bool,
+    pub retry_count: i32,
+    pub next_retry_at: Option<DateTime<Utc>>,
+    /// Serialized `AudioTimings`
+    pub timings: Option<serde_json::Value>,
     pub created_at: DateTime<Utc>,
 }
 
 impl AudioFile {
-    pub fn to_response(self) -> AudioResponse {
+    /// Path of the endpoint that serves this file's raw audio
+    pub fn stream_path(&self) -> String {
+        format!("/api/audio/{}/stream", self.id)
+    }
+
+    /// Timings recorded at synthesis, if any
+    pub fn recorded_timings(&self) -> Option<AudioTimings> {
+        self.timings
+            .clone()
+            .and_then(|value| serde_json::from_value(value).ok())
+    }
+
+    pub fn into_response(self) -> AudioResponse {
         AudioResponse {
+            audio_url: self.stream_path(),
             id: self.id,
             summary_id: self.summary_id,
             language: self.language,
             voice_type: self.voice_type,
             duration_ms: self.duration_ms,
             file_size_kb: self.file_size_kb,
-            audio_url: self.file_url,
+            content_type: self.content_type,
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            bit_depth: self.bit_depth,
+            is_placeholder: self.is_placeholder,
             created_at: self.created_at,
         }
     }
@@ -47,7 +81,43 @@ pub struct CreateAudioFile {
     pub summary_id: Uuid,
     pub language: String,
     pub voice_type: String,
-    pub file_url: String,
+    pub blob_key: String,
+    pub content_type: String,
     pub duration_ms: Option<i32>,
     pub file_size_kb: Option<i32>,
+    pub sample_rate: Option<i32>,
+    pub channels: Option<i16>,
+    pub bit_depth: Option<i16>,
+    pub is_placeholder: bool,
+    pub next_retry_at: Option<DateTime<Utc>>,
+    pub timings: serde_json::Value,
+}
+
+/// A sentence or word of `summary_text` and when it is spoken in the audio.
+///
+/// `char_start` and `char_end` are character (not byte) offsets into `summary_text`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimedSegment {
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub char_start: usize,
+    pub char_end: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AudioTimings {
+    pub sentences: Vec<TimedSegment>,
+    pub words: Vec<TimedSegment>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AudioTimingsResponse {
+    pub audio_id: Uuid,
+    pub summary_id: Uuid,
+    pub duration_ms: Option<i32>,
+    /// Timings were estimated from text length because none were recorded at synthesis
+    pub estimated: bool,
+    pub sentences: Vec<TimedSegment>,
+    pub words: Vec<TimedSegment>,
 }
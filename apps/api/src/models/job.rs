@@ -0,0 +1,163 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::SummaryResponse;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    FetchingContent,
+    Chunking,
+    Summarizing,
+    Saving,
+    Done,
+    Failed,
+}
+
+impl std::str::FromStr for JobStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "queued" => Ok(JobStatus::Queued),
+            "fetching_content" => Ok(JobStatus::FetchingContent),
+            "chunking" => Ok(JobStatus::Chunking),
+            "summarizing" => Ok(JobStatus::Summarizing),
+            "saving" => Ok(JobStatus::Saving),
+            "done" => Ok(JobStatus::Done),
+            "failed" => Ok(JobStatus::Failed),
+            _ => Err(format!("Unknown job status: {}", s)),
+        }
+    }
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::FetchingContent => "fetching_content",
+            JobStatus::Chunking => "chunking",
+            JobStatus::Summarizing => "summarizing",
+            JobStatus::Saving => "saving",
+            JobStatus::Done => "done",
+            JobStatus::Failed => "failed",
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, JobStatus::Done | JobStatus::Failed)
+    }
+}
+
+/// Progress event streamed to clients while a summary job runs
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SummaryEvent {
+    Status {
+        status: JobStatus,
+    },
+    ContentFetched {
+        characters: usize,
+    },
+    Chunked {
+        chunks: usize,
+    },
+    ChunkSummary {
+        index: usize,
+        total: usize,
+        summary: String,
+    },
+    Completed {
+        summary: Box<SummaryResponse>,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+impl SummaryEvent {
+    /// SSE event name
+    pub fn name(&self) -> &'static str {
+        match self {
+            SummaryEvent::Status { .. } => "status",
+            SummaryEvent::ContentFetched { .. } => "content_fetched",
+            SummaryEvent::Chunked { .. } => "chunked",
+            SummaryEvent::ChunkSummary { .. } => "chunk_summary",
+            SummaryEvent::Completed { .. } => "completed",
+            SummaryEvent::Failed { .. } => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobAccepted {
+    pub job_id: Uuid,
+    pub status: String,
+    pub status_url: String,
+    pub events_url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobResponse {
+    pub id: Uuid,
+    pub book_id: String,
+    pub status: String,
+    pub error: Option<String>,
+    pub summary: Option<SummaryResponse>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct SummaryJob {
+    pub id: Uuid,
+    pub book_id: String,
+    pub language: String,
+    pub style: String,
+    pub max_pages: i32,
+    pub status: String,
+    pub summary_id: Option<Uuid>,
+    pub error: Option<String>,
+    pub force_regenerate: bool,
+    pub format: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SummaryJob {
+    pub fn accepted(&self) -> JobAccepted {
+        JobAccepted {
+            job_id: self.id,
+            status: self.status.clone(),
+            status_url: format!("/api/jobs/{}", self.id),
+            events_url: format!("/api/jobs/{}/events", self.id),
+        }
+    }
+
+    pub fn into_response(self, summary: Option<SummaryResponse>) -> JobResponse {
+        JobResponse {
+            id: self.id,
+            book_id: self.book_id,
+            status: self.status,
+            error: self.error,
+            summary,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateSummaryJob {
+    pub book_id: String,
+    pub language: String,
+    pub style: String,
+    pub max_pages: i32,
+    pub status: JobStatus,
+    pub summary_id: Option<Uuid>,
+    pub force_regenerate: bool,
+    pub format: String,
+}
@@ -1,11 +1,21 @@
 pub mod api_response;
 pub mod audio;
 pub mod book;
+pub mod job;
 pub mod search;
 pub mod summary;
+pub mod voice;
 
 pub use api_response::HealthResponse;
-pub use audio::{AudioFile, CreateAudioFile};
+pub use audio::{
+    AudioFile, AudioResponse, AudioTimings, AudioTimingsResponse, CreateAudioFile, TimedSegment,
+};
 pub use book::{Book, BookDetail, BookSource, VolumeInfo};
+pub use job::{CreateSummaryJob, JobAccepted, JobResponse, JobStatus, SummaryEvent, SummaryJob};
 pub use search::{ExtractedTerms, QueryIntent, SearchRequest, SearchResponse};
-pub use summary::{CreateSummary, Summary, SummaryRequest, SummaryResponse};
+pub use summary::{
+    ChunkSummary, ContentOrigin, CreateSummary, CreateSummaryChapter, StructuredSummary, Summary,
+    SummaryChapter, SummaryChaptersResponse, SummaryMethod, SummaryRequest, SummaryResponse,
+    SummaryVersionsQuery, TranslationRequest,
+};
+pub use voice::{Voice, VoicesQuery};
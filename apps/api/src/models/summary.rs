@@ -10,6 +10,33 @@ pub struct SummaryRequest {
     #[serde(default = "default_style")]
     pub style: String,
     pub max_pages: Option<usize>,
+    /// `plain` for running text, or `structured` for a sectioned summary
+    #[serde(default = "default_format")]
+    pub format: String,
+    /// Skip stored summaries and generate a new version
+    #[serde(default)]
+    pub force_regenerate: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SummaryVersionsQuery {
+    #[serde(default = "default_language")]
+    pub language: String,
+    #[serde(default = "default_style")]
+    pub style: String,
+    /// Only versions written to this page budget
+    pub max_pages: Option<usize>,
+    /// Only versions in this format
+    pub format: Option<String>,
+    /// Set to `false` to leave out translations of other summaries
+    #[serde(default = "default_include_translations")]
+    pub include_translations: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TranslationRequest {
+    /// Language to translate the summary into
+    pub language: String,
 }
 
 fn default_style() -> String {
@@ -20,16 +47,142 @@ fn default_language() -> String {
     "en".to_string()
 }
 
+fn default_include_translations() -> bool {
+    true
+}
+
+fn default_format() -> String {
+    "plain".to_string()
+}
+
+/// Summary split into sections, produced with `format=structured`.
+///
+/// Matches the schema served at `/api/summaries/schema`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuredSummary {
+    pub overview: String,
+    /// Main turns of the story (or argument), in order
+    pub plot_arc: Vec<String>,
+    pub characters: Vec<StructuredCharacter>,
+    pub themes: Vec<String>,
+    /// Passages copied verbatim from the book
+    pub quotes: Vec<String>,
+    /// Who the book suits and how demanding it is to read
+    pub reading_level: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuredCharacter {
+    pub name: String,
+    /// One line on who the character is
+    pub description: String,
+}
+
+/// How a summary's text was produced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryMethod {
+    /// Written by the summarization model
+    Abstractive,
+    /// Sentences selected from the source by the offline TextRank fallback
+    Extractive,
+}
+
+impl SummaryMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SummaryMethod::Abstractive => "abstractive",
+            SummaryMethod::Extractive => "extractive",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "abstractive" => Some(SummaryMethod::Abstractive),
+            "extractive" => Some(SummaryMethod::Extractive),
+            _ => None,
+        }
+    }
+}
+
+/// Where the text handed to the summarizer came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentOrigin {
+    /// Full book text fetched from the provider
+    FullText,
+    /// The provider's description of the book
+    Description,
+    /// Title, authors and description assembled after content extraction failed
+    FallbackContent,
+    /// A placeholder sentence used when nothing else was available
+    MinimalFallback,
+}
+
+impl ContentOrigin {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentOrigin::FullText => "full_text",
+            ContentOrigin::Description => "description",
+            ContentOrigin::FallbackContent => "fallback_content",
+            ContentOrigin::MinimalFallback => "minimal_fallback",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "full_text" => Some(ContentOrigin::FullText),
+            "description" => Some(ContentOrigin::Description),
+            "fallback_content" => Some(ContentOrigin::FallbackContent),
+            "minimal_fallback" => Some(ContentOrigin::MinimalFallback),
+            _ => None,
+        }
+    }
+
+    /// 0 for a model summary of the full text, 1 when the input was a description or
+    /// fallback content, 2 when the summary itself is extractive
+    pub fn fallback_level(&self, method: SummaryMethod) -> i32 {
+        match (self, method) {
+            (_, SummaryMethod::Extractive) => 2,
+            (ContentOrigin::FullText, SummaryMethod::Abstractive) => 0,
+            _ => 1,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SummaryResponse {
     pub id: Uuid,
     pub summary_text: String,
     pub language: String,
     pub word_count: i32,
+    pub version: i32,
+    pub method: String,
+    /// Summary this one was translated from, if it is a translation
+    pub translated_from: Option<Uuid>,
+    pub format: String,
+    /// Sections of a structured summary
+    pub structured: Option<StructuredSummary>,
+    /// Plain-text rendering of `structured` with section headings, which is what audio reads
+    pub structured_text: Option<String>,
+    pub provenance: SummaryProvenance,
     pub book_info: BookInfo,
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryProvenance {
+    pub content_origin: String,
+    pub input_chars: i32,
+    pub truncated: bool,
+    pub model: String,
+    pub generation_params: serde_json::Value,
+    pub chunk_count: i32,
+    pub fallback_level: i32,
+    /// Language identified in the source content, when it could be told
+    pub detected_language: Option<String>,
+    /// Page budget the summary was written to
+    pub max_pages: i32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BookInfo {
     pub title: String,
@@ -49,17 +202,63 @@ pub struct Summary {
     pub word_count: i32,
     pub style: String,
     pub source_hash: String,
+    pub method: String,
+    pub content_origin: String,
+    pub input_chars: i32,
+    pub truncated: bool,
+    pub model: String,
+    pub generation_params: serde_json::Value,
+    pub chunk_count: i32,
+    pub fallback_level: i32,
+    pub version: i32,
+    pub translated_from: Option<Uuid>,
+    pub detected_language: Option<String>,
+    pub max_pages: i32,
+    pub format: String,
+    pub structured: Option<serde_json::Value>,
+    pub structured_text: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 impl Summary {
-    pub fn to_response(self) -> SummaryResponse {
+    /// Extractive summaries are stopgaps and are never served in place of a new one
+    pub fn is_reusable(&self) -> bool {
+        self.method == SummaryMethod::Abstractive.as_str()
+    }
+
+    /// Text read out for audio: the structured rendering when there is one
+    pub fn spoken_text(&self) -> &str {
+        self.structured_text
+            .as_deref()
+            .unwrap_or(&self.summary_text)
+    }
+
+    pub fn into_response(self) -> SummaryResponse {
         SummaryResponse {
             id: self.id,
             summary_text: self.summary_text,
             language: self.language,
             word_count: self.word_count,
+            version: self.version,
+            method: self.method,
+            translated_from: self.translated_from,
+            format: self.format,
+            structured: self
+                .structured
+                .and_then(|structured| serde_json::from_value(structured).ok()),
+            structured_text: self.structured_text,
+            provenance: SummaryProvenance {
+                content_origin: self.content_origin,
+                input_chars: self.input_chars,
+                truncated: self.truncated,
+                model: self.model,
+                generation_params: self.generation_params,
+                chunk_count: self.chunk_count,
+                fallback_level: self.fallback_level,
+                detected_language: self.detected_language,
+                max_pages: self.max_pages,
+            },
             book_info: BookInfo {
                 title: self.book_title,
                 author: self.book_author,
@@ -70,6 +269,61 @@ impl Summary {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChapterResponse {
+    pub index: i32,
+    pub title: String,
+    pub summary_text: String,
+    pub word_count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryChaptersResponse {
+    pub summary: SummaryResponse,
+    pub chapters: Vec<ChapterResponse>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct SummaryChapter {
+    pub id: Uuid,
+    pub summary_id: Uuid,
+    pub chapter_index: i32,
+    pub title: String,
+    pub summary_text: String,
+    pub word_count: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SummaryChapter {
+    pub fn into_response(self) -> ChapterResponse {
+        ChapterResponse {
+            index: self.chapter_index,
+            title: self.title,
+            summary_text: self.summary_text,
+            word_count: self.word_count,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateSummaryChapter {
+    pub chapter_index: i32,
+    pub title: String,
+    pub summary_text: String,
+    pub word_count: i32,
+}
+
+/// Summary of one chunk (or merged group of summaries), reusable across runs
+#[derive(Debug, FromRow)]
+pub struct ChunkSummary {
+    pub chunk_hash: String,
+    pub model: String,
+    pub style: String,
+    pub language: String,
+    pub summary_text: String,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug)]
 pub struct CreateSummary {
     pub book_id: String,
@@ -81,4 +335,17 @@ pub struct CreateSummary {
     pub word_count: i32,
     pub style: String,
     pub source_hash: String,
+    pub method: SummaryMethod,
+    pub content_origin: ContentOrigin,
+    pub input_chars: i32,
+    pub truncated: bool,
+    pub model: String,
+    pub generation_params: serde_json::Value,
+    pub chunk_count: i32,
+    pub translated_from: Option<Uuid>,
+    pub detected_language: Option<String>,
+    pub max_pages: i32,
+    pub format: String,
+    pub structured: Option<serde_json::Value>,
+    pub structured_text: Option<String>,
 }
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// A selectable text-to-speech voice offered by the active inference backend
+#[derive(Debug, Clone, Serialize)]
+pub struct Voice {
+    /// Identifier passed as `voice_type` when requesting audio
+    pub id: String,
+    pub language: String,
+    pub model: String,
+    /// Speaker within a multi-speaker model, if the model needs one
+    pub speaker_id: Option<String>,
+    pub gender: String,
+    pub description: String,
+    /// Used when no `voice_type` (or `default`) is requested for the language
+    pub is_default: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VoicesQuery {
+    pub language: Option<String>,
+}
@@ -1,62 +1,52 @@
 use std::collections::HashMap;
 
+use futures::future::join_all;
+
 use crate::{
     models::{Book, BookDetail, BookSource},
-    services::books::{GoogleBooksService, GutenbergService, OpenLibraryService},
+    services::books::BookProviderRegistry,
     utils::errors::{AppError, Result},
 };
 
 pub struct BookAggregatorService {
-    google_books: GoogleBooksService,
-    open_library: OpenLibraryService,
-    gutenberg: GutenbergService,
+    providers: BookProviderRegistry,
 }
 
 impl BookAggregatorService {
-    pub fn new(
-        google_books: GoogleBooksService,
-        open_library: OpenLibraryService,
-        gutenberg: GutenbergService,
-    ) -> Self {
-        Self {
-            google_books,
-            open_library,
-            gutenberg,
-        }
+    pub fn new(providers: BookProviderRegistry) -> Self {
+        Self { providers }
     }
 
     pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<Book>> {
-        let per_source = (limit / 3).max(5);
+        if self.providers.is_empty() {
+            tracing::warn!("No book providers registered, returning empty search results");
+            return Ok(Vec::new());
+        }
+
+        let per_source = (limit / self.providers.len()).max(5);
 
         // Search all sources concurrently
-        let (google_result, openlibrary_result, gutenberg_result) = tokio::join!(
-            self.google_books.search(query, per_source),
-            self.open_library.search(query, per_source),
-            self.gutenberg.search(query, per_source)
-        );
+        let results = join_all(
+            self.providers
+                .providers()
+                .iter()
+                .map(|provider| provider.search(query, per_source)),
+        )
+        .await;
 
         let mut all_books = Vec::new();
 
         // Collect results from each source
-        if let Ok(books) = google_result {
-            tracing::debug!("Google Books returned {} results", books.len());
-            all_books.extend(books);
-        } else if let Err(e) = google_result {
-            tracing::warn!("Google Books search failed: {}", e);
-        }
-
-        if let Ok(books) = openlibrary_result {
-            tracing::debug!("Open Library returned {} results", books.len());
-            all_books.extend(books);
-        } else if let Err(e) = openlibrary_result {
-            tracing::warn!("Open Library search failed: {}", e);
-        }
-
-        if let Ok(books) = gutenberg_result {
-            tracing::debug!("Gutenberg returned {} results", books.len());
-            all_books.extend(books);
-        } else if let Err(e) = gutenberg_result {
-            tracing::warn!("Gutenberg search failed: {}", e);
+        for (provider, result) in self.providers.providers().iter().zip(results) {
+            match result {
+                Ok(books) => {
+                    tracing::debug!("{} returned {} results", provider.id_prefix(), books.len());
+                    all_books.extend(books);
+                }
+                Err(e) => {
+                    tracing::warn!("{} search failed: {}", provider.id_prefix(), e);
+                }
+            }
         }
 
         tracing::info!("Total books before deduplication: {}", all_books.len());
@@ -83,19 +73,12 @@ impl BookAggregatorService {
 
         let (source, book_id) = (parts[0], parts[1]);
 
-        let book = match source {
-            "google" => self.google_books.get_by_id(book_id).await?,
-            "openlibrary" => self.open_library.get_by_id(book_id).await?,
-            "gutenberg" => {
-                let gid: i32 = book_id
-                    .parse()
-                    .map_err(|_| AppError::InvalidInput("Invalid Gutenberg ID".to_string()))?;
-                self.gutenberg.get_by_id(gid).await?
-            }
-            _ => return Err(AppError::InvalidInput("Unknown book source".to_string())),
-        };
+        let provider = self
+            .providers
+            .get(source)
+            .ok_or_else(|| AppError::InvalidInput("Unknown book source".to_string()))?;
 
-        if let Some(book) = book {
+        if let Some(book) = provider.get_by_id(book_id).await? {
             let detail = self.enrich_book_detail(book).await;
             Ok(Some(detail))
         } else {
@@ -109,7 +92,7 @@ impl BookAggregatorService {
 
         for book in books {
             let key = self.create_dedup_key(&book);
-            book_groups.entry(key).or_insert_with(Vec::new).push(book);
+            book_groups.entry(key).or_default().push(book);
         }
 
         let mut deduplicated = Vec::new();
@@ -118,8 +101,8 @@ impl BookAggregatorService {
         for (_, mut group) in book_groups {
             // Sort by source priority first, then by completeness/quality
             group.sort_by(|a, b| {
-                let a_priority = self.get_source_priority(&a.source);
-                let b_priority = self.get_source_priority(&b.source);
+                let a_priority = self.get_source_priority(a);
+                let b_priority = self.get_source_priority(b);
 
                 // Lower number = higher priority
                 if a_priority != b_priority {
@@ -169,18 +152,18 @@ impl BookAggregatorService {
     fn normalize_string(&self, s: &str) -> String {
         s.to_lowercase()
             .trim()
-            .replace(&[' ', '-', '_', ':', '.', ',', ';', '(', ')', '[', ']'], "")
+            .replace([' ', '-', '_', ':', '.', ',', ';', '(', ')', '[', ']'], "")
             .chars()
             .filter(|c| c.is_alphanumeric())
             .collect()
     }
 
-    fn get_source_priority(&self, source: &BookSource) -> u8 {
-        match source {
-            BookSource::Gutenberg => 1,   // Highest priority - full text available
-            BookSource::OpenLibrary => 2, // Medium priority - good metadata
-            BookSource::Google => 3,      // Lowest priority - commercial/limited
-        }
+    fn get_source_priority(&self, book: &Book) -> u8 {
+        book.id
+            .split(':')
+            .next()
+            .and_then(|prefix| self.providers.get(prefix))
+            .map_or(u8::MAX, |provider| provider.priority())
     }
 
     fn calculate_completeness_score(&self, book: &Book) -> f32 {
@@ -278,27 +261,18 @@ impl BookAggregatorService {
     }
 
     async fn enrich_book_detail(&self, book: Book) -> BookDetail {
-        let content_url = match book.source {
-            BookSource::Gutenberg => {
-                let id = book.id.replace("gutenberg:", "");
-                Some(format!(
-                    "https://www.gutenberg.org/files/{}/{}-0.txt",
-                    id, id
-                ))
-            }
-            BookSource::OpenLibrary => {
-                // Try to get Internet Archive identifier
-                let ol_key = book.id.replace("openlibrary:", "");
-                if let Ok(Some(ia_id)) = self.open_library.get_ia_identifier(&ol_key).await {
-                    Some(format!(
-                        "https://archive.org/download/{}/{}.txt",
-                        ia_id, ia_id
-                    ))
-                } else {
-                    None
-                }
-            }
-            _ => None,
+        let provider = book
+            .id
+            .split(':')
+            .next()
+            .and_then(|prefix| self.providers.get(prefix));
+
+        let content_url = match provider {
+            Some(provider) => provider.content_url(&book).await.unwrap_or_else(|e| {
+                tracing::warn!("Content lookup failed for {}: {}", book.id, e);
+                None
+            }),
+            None => None,
         };
 
         let gutenberg_id = if book.source == BookSource::Gutenberg {
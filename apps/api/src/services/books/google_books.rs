@@ -1,8 +1,10 @@
+use async_trait::async_trait;
 use reqwest::Client;
 use serde::Deserialize;
 
 use crate::{
     models::{Book, BookSource, VolumeInfo},
+    services::books::BookProvider,
     utils::errors::{AppError, Result},
 };
 
@@ -20,17 +22,34 @@ struct BookItem {
 
 pub struct GoogleBooksService {
     client: Client,
+    base_url: String,
     api_key: Option<String>,
 }
 
 impl GoogleBooksService {
-    pub fn new(client: Client, api_key: Option<String>) -> Self {
-        Self { client, api_key }
+    pub fn new(client: Client, base_url: String, api_key: Option<String>) -> Self {
+        Self {
+            client,
+            base_url,
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl BookProvider for GoogleBooksService {
+    fn id_prefix(&self) -> &str {
+        "google"
     }
 
-    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<Book>> {
+    fn priority(&self) -> u8 {
+        3 // Lowest priority - commercial/limited
+    }
+
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<Book>> {
         let mut url = format!(
-            "https://www.googleapis.com/books/v1/volumes?q={}&maxResults={}",
+            "{}/volumes?q={}&maxResults={}",
+            self.base_url,
             urlencoding::encode(query),
             limit
         );
@@ -58,8 +77,8 @@ impl GoogleBooksService {
             .collect())
     }
 
-    pub async fn get_by_id(&self, id: &str) -> Result<Option<Book>> {
-        let mut url = format!("https://www.googleapis.com/books/v1/volumes/{}", id);
+    async fn get_by_id(&self, id: &str) -> Result<Option<Book>> {
+        let mut url = format!("{}/volumes/{}", self.base_url, id);
 
         if let Some(key) = &self.api_key {
             url.push_str(&format!("?key={}", key));
@@ -82,7 +101,9 @@ impl GoogleBooksService {
 
         Ok(Some(self.convert_to_book(item)))
     }
+}
 
+impl GoogleBooksService {
     fn convert_to_book(&self, item: BookItem) -> Book {
         let volume = item.volume_info;
         let isbn = volume.extract_isbn();
@@ -1,11 +1,17 @@
+use async_trait::async_trait;
 use reqwest::Client;
 use serde::Deserialize;
+use std::time::Duration;
 
 use crate::{
     models::{Book, BookSource},
+    services::books::BookProvider,
     utils::errors::{AppError, Result},
 };
 
+// Gutendex can be slow; keep its requests well below the shared client's timeout
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Deserialize)]
 struct GutenbergResponse {
     results: Vec<GutenbergBook>,
@@ -30,28 +36,42 @@ struct Author {
 pub struct GutenbergService {
     client: Client,
     base_url: String,
+    content_base_url: String,
 }
 
 impl GutenbergService {
-    pub fn new(_client: Client, base_url: String) -> Self {
-        // Create a new client that follows redirects for Gutenberg API
-        let client = Client::builder()
-            .redirect(reqwest::redirect::Policy::limited(10))
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .unwrap_or_else(|_| Client::new());
-
-        Self { client, base_url }
+    pub fn new(client: Client, base_url: String, content_base_url: String) -> Self {
+        Self {
+            client,
+            base_url,
+            content_base_url,
+        }
+    }
+}
+
+#[async_trait]
+impl BookProvider for GutenbergService {
+    fn id_prefix(&self) -> &str {
+        "gutenberg"
     }
 
-    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<Book>> {
+    fn priority(&self) -> u8 {
+        1 // Highest priority - full text available
+    }
+
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<Book>> {
         let url = format!(
             "{}/books/?search={}",
             self.base_url,
             urlencoding::encode(query)
         );
 
-        let response = self.client.get(&url).send().await?;
+        let response = self
+            .client
+            .get(&url)
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await?;
 
         if !response.status().is_success() {
             return Err(AppError::ExternalApi(format!(
@@ -70,10 +90,18 @@ impl GutenbergService {
             .collect())
     }
 
-    pub async fn get_by_id(&self, id: i32) -> Result<Option<Book>> {
+    async fn get_by_id(&self, id: &str) -> Result<Option<Book>> {
+        let id: i32 = id
+            .parse()
+            .map_err(|_| AppError::InvalidInput("Invalid Gutenberg ID".to_string()))?;
         let url = format!("{}/books/{}/", self.base_url, id);
 
-        let response = self.client.get(&url).send().await?;
+        let response = self
+            .client
+            .get(&url)
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await?;
 
         if response.status().as_u16() == 404 {
             return Ok(None);
@@ -91,6 +119,16 @@ impl GutenbergService {
         Ok(Some(self.convert_to_book(book)))
     }
 
+    async fn content_url(&self, book: &Book) -> Result<Option<String>> {
+        let id = book.id.trim_start_matches("gutenberg:");
+        Ok(Some(format!(
+            "{}/files/{}/{}-0.txt",
+            self.content_base_url, id, id
+        )))
+    }
+}
+
+impl GutenbergService {
     fn convert_to_book(&self, book: GutenbergBook) -> Book {
         let authors: Vec<String> = book.authors.into_iter().map(|a| a.name).collect();
 
@@ -117,7 +155,7 @@ impl GutenbergService {
             page_count: None,
             language: book.languages.first().cloned(),
             cover_url,
-            preview_link: Some(format!("https://www.gutenberg.org/ebooks/{}", book.id)),
+            preview_link: Some(format!("{}/ebooks/{}", self.content_base_url, book.id)),
             source: BookSource::Gutenberg,
         }
     }
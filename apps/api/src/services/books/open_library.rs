@@ -1,8 +1,10 @@
+use async_trait::async_trait;
 use reqwest::Client;
 use serde::Deserialize;
 
 use crate::{
     models::{Book, BookSource},
+    services::books::BookProvider,
     utils::errors::{AppError, Result},
 };
 
@@ -34,16 +36,41 @@ struct OpenLibraryDoc {
 
 pub struct OpenLibraryService {
     client: Client,
+    base_url: String,
+    covers_base_url: String,
+    archive_base_url: String,
 }
 
 impl OpenLibraryService {
-    pub fn new(client: Client) -> Self {
-        Self { client }
+    pub fn new(
+        client: Client,
+        base_url: String,
+        covers_base_url: String,
+        archive_base_url: String,
+    ) -> Self {
+        Self {
+            client,
+            base_url,
+            covers_base_url,
+            archive_base_url,
+        }
+    }
+}
+
+#[async_trait]
+impl BookProvider for OpenLibraryService {
+    fn id_prefix(&self) -> &str {
+        "openlibrary"
+    }
+
+    fn priority(&self) -> u8 {
+        2 // Medium priority - good metadata
     }
 
-    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<Book>> {
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<Book>> {
         let url = format!(
-            "https://openlibrary.org/search.json?q={}&limit={}",
+            "{}/search.json?q={}&limit={}",
+            self.base_url,
             urlencoding::encode(query),
             limit
         );
@@ -66,8 +93,8 @@ impl OpenLibraryService {
             .collect())
     }
 
-    pub async fn get_by_id(&self, id: &str) -> Result<Option<Book>> {
-        let url = format!("https://openlibrary.org{}.json", id);
+    async fn get_by_id(&self, id: &str) -> Result<Option<Book>> {
+        let url = format!("{}{}.json", self.base_url, id);
 
         let response = self.client.get(&url).send().await?;
 
@@ -87,10 +114,21 @@ impl OpenLibraryService {
         Ok(Some(self.convert_to_book(doc)))
     }
 
+    async fn content_url(&self, book: &Book) -> Result<Option<String>> {
+        // Try to get Internet Archive identifier
+        let ol_key = book.id.trim_start_matches("openlibrary:");
+        Ok(self
+            .get_ia_identifier(ol_key)
+            .await?
+            .map(|ia_id| format!("{}/download/{}/{}.txt", self.archive_base_url, ia_id, ia_id)))
+    }
+}
+
+impl OpenLibraryService {
     fn convert_to_book(&self, doc: OpenLibraryDoc) -> Book {
         let cover_url = doc
             .cover_i
-            .map(|id| format!("https://covers.openlibrary.org/b/id/{}-L.jpg", id));
+            .map(|id| format!("{}/b/id/{}-L.jpg", self.covers_base_url, id));
 
         let description = if !doc.subject.is_empty() {
             Some(
@@ -119,7 +157,7 @@ impl OpenLibraryService {
             page_count: doc.number_of_pages_median,
             language: doc.language.first().cloned(),
             cover_url,
-            preview_link: Some(format!("https://openlibrary.org{}", doc.key)),
+            preview_link: Some(format!("{}{}", self.base_url, doc.key)),
             source: if has_ia_content {
                 // If it has IA content, we can treat it like a content source
                 BookSource::OpenLibrary
@@ -131,7 +169,7 @@ impl OpenLibraryService {
 
     // Helper method to extract Internet Archive identifier from book
     pub async fn get_ia_identifier(&self, openlibrary_key: &str) -> Result<Option<String>> {
-        let url = format!("https://openlibrary.org{}.json", openlibrary_key);
+        let url = format!("{}{}.json", self.base_url, openlibrary_key);
 
         let response = self.client.get(&url).send().await?;
 
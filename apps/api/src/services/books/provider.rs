@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{models::Book, utils::errors::Result};
+
+/// A source of book metadata (and optionally full text) that the aggregator can query.
+///
+/// Book IDs handed out by a provider must be of the form `<id_prefix>:<provider id>`
+/// so the aggregator can route detail lookups back to the provider that produced them.
+#[async_trait]
+pub trait BookProvider: Send + Sync {
+    /// Prefix used in book IDs, e.g. `"gutenberg"` for `gutenberg:1342`
+    fn id_prefix(&self) -> &str;
+
+    /// Deduplication priority; lower number = higher priority
+    fn priority(&self) -> u8;
+
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<Book>>;
+
+    /// Look up a book by the provider-specific part of its ID (without the prefix)
+    async fn get_by_id(&self, id: &str) -> Result<Option<Book>>;
+
+    /// Resolve a URL to the plain-text content of a book, if the provider has one
+    async fn content_url(&self, _book: &Book) -> Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+/// Ordered set of book providers, keyed by their ID prefix
+#[derive(Clone, Default)]
+pub struct BookProviderRegistry {
+    providers: Vec<Arc<dyn BookProvider>>,
+}
+
+impl BookProviderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a provider, replacing any existing provider with the same ID prefix
+    pub fn register(mut self, provider: Arc<dyn BookProvider>) -> Self {
+        self.providers
+            .retain(|p| p.id_prefix() != provider.id_prefix());
+        self.providers.push(provider);
+        self
+    }
+
+    pub fn get(&self, id_prefix: &str) -> Option<&Arc<dyn BookProvider>> {
+        self.providers.iter().find(|p| p.id_prefix() == id_prefix)
+    }
+
+    pub fn providers(&self) -> &[Arc<dyn BookProvider>] {
+        &self.providers
+    }
+
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+}
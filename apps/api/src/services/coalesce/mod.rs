@@ -0,0 +1,198 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::broadcast;
+
+use crate::utils::errors::{AppError, Result};
+
+type InFlight<T> = Arc<Mutex<HashMap<String, broadcast::Sender<std::result::Result<T, String>>>>>;
+
+/// Coalesces concurrent calls that share a key into a single computation.
+///
+/// The first caller for a key runs the work; callers arriving while it is in flight
+/// wait for and share its result. Followers receive errors as `ServiceError`.
+pub struct SingleFlight<T> {
+    in_flight: InFlight<T>,
+}
+
+impl<T> Clone for SingleFlight<T> {
+    fn clone(&self) -> Self {
+        Self {
+            in_flight: self.in_flight.clone(),
+        }
+    }
+}
+
+impl<T: Clone> SingleFlight<T> {
+    pub fn new() -> Self {
+        Self {
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub async fn run<F, Fut>(&self, key: &str, work: F) -> Result<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let follower = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(key) {
+                Some(sender) => Some(sender.subscribe()),
+                None => {
+                    let (sender, _) = broadcast::channel(1);
+                    in_flight.insert(key.to_string(), sender);
+                    None
+                }
+            }
+        };
+
+        if let Some(mut receiver) = follower {
+            tracing::info!("Joining in-flight request for {}", key);
+            return match receiver.recv().await {
+                Ok(Ok(value)) => Ok(value),
+                Ok(Err(message)) => Err(AppError::ServiceError(message)),
+                Err(_) => Err(AppError::ServiceError(
+                    "The shared request was cancelled, please try again".to_string(),
+                )),
+            };
+        }
+
+        // Removes the entry even if this caller is cancelled mid-flight
+        let guard = FlightGuard {
+            in_flight: &self.in_flight,
+            key,
+            finished: false,
+        };
+        let result = work().await;
+
+        if let Some(sender) = guard.finish() {
+            let shared = match &result {
+                Ok(value) => Ok(value.clone()),
+                Err(e) => Err(e.to_string()),
+            };
+            // No receivers simply means nobody else asked
+            let _ = sender.send(shared);
+        }
+
+        result
+    }
+}
+
+struct FlightGuard<'a, T> {
+    in_flight: &'a InFlight<T>,
+    key: &'a str,
+    finished: bool,
+}
+
+impl<T> FlightGuard<'_, T> {
+    fn finish(mut self) -> Option<broadcast::Sender<std::result::Result<T, String>>> {
+        self.finished = true;
+        self.in_flight.lock().unwrap().remove(self.key)
+    }
+}
+
+impl<T> Drop for FlightGuard<'_, T> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.in_flight.lock().unwrap().remove(self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use tokio::sync::Notify;
+
+    #[tokio::test]
+    async fn concurrent_calls_share_one_computation() {
+        let flights = SingleFlight::<u32>::new();
+        let calls = AtomicUsize::new(0);
+        let release = Notify::new();
+
+        let work = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            release.notified().await;
+            Ok(42)
+        };
+        let leader = flights.run("key", work);
+        let follower = async {
+            // Let the leader register before joining it
+            tokio::task::yield_now().await;
+            let result = flights.run("key", work).await;
+            result
+        };
+        let notify = async {
+            tokio::task::yield_now().await;
+            tokio::task::yield_now().await;
+            release.notify_one();
+        };
+
+        let (leader, follower, _) = tokio::join!(leader, follower, notify);
+        assert_eq!(leader.unwrap(), 42);
+        assert_eq!(follower.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn followers_receive_the_leaders_error() {
+        let flights = SingleFlight::<u32>::new();
+        let release = Notify::new();
+
+        let leader = flights.run("key", || async {
+            release.notified().await;
+            Err(AppError::ExternalApi("backend down".to_string()))
+        });
+        let follower = async {
+            tokio::task::yield_now().await;
+            flights
+                .run("key", || async { panic!("follower must not run the work") })
+                .await
+        };
+        let notify = async {
+            tokio::task::yield_now().await;
+            tokio::task::yield_now().await;
+            release.notify_one();
+        };
+
+        let (leader, follower, _) = tokio::join!(leader, follower, notify);
+        assert!(matches!(leader, Err(AppError::ExternalApi(_))));
+        match follower {
+            Err(AppError::ServiceError(message)) => assert!(message.contains("backend down")),
+            other => panic!("unexpected follower result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn finished_and_cancelled_flights_free_their_key() {
+        let flights = SingleFlight::<u32>::new();
+
+        assert_eq!(flights.run("key", || async { Ok(1) }).await.unwrap(), 1);
+        assert_eq!(flights.run("key", || async { Ok(2) }).await.unwrap(), 2);
+
+        let cancelled = tokio::time::timeout(
+            Duration::from_millis(10),
+            flights.run("key", std::future::pending::<Result<u32>>),
+        )
+        .await;
+        assert!(cancelled.is_err());
+        assert!(flights.in_flight.lock().unwrap().is_empty());
+        assert_eq!(flights.run("key", || async { Ok(3) }).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn different_keys_run_separately() {
+        let flights = SingleFlight::<&'static str>::new();
+        let (a, b) = tokio::join!(
+            flights.run("a", || async { Ok("a") }),
+            flights.run("b", || async { Ok("b") })
+        );
+
+        assert_eq!((a.unwrap(), b.unwrap()), ("a", "b"));
+    }
+}
@@ -57,6 +57,9 @@ impl HuggingFaceClient {
                 .post(&url)
                 .header("Authorization", format!("Bearer {}", self.token))
                 .header("Content-Type", "application/json")
+                // Speech models answer with FLAC unless asked otherwise, and chunked
+                // speech can only be joined from WAV
+                .header("Accept", "audio/wav")
                 .json(&payload)
                 .timeout(std::time::Duration::from_secs(120))
                 .send()
@@ -199,10 +202,9 @@ impl HuggingFaceClient {
                             Ok(result) => {
                                 if let Some(first_result) = result.first() {
                                     if let Some(text) = &first_result.generated_text {
-                                        let cleaned_text = if text.starts_with(inputs) {
-                                            text[inputs.len()..].trim().to_string()
-                                        } else {
-                                            text.clone()
+                                        let cleaned_text = match text.strip_prefix(inputs) {
+                                            Some(rest) => rest.trim().to_string(),
+                                            None => text.clone(),
                                         };
 
                                         if !cleaned_text.trim().is_empty() {
@@ -297,6 +299,98 @@ impl HuggingFaceClient {
         }
     }
 
+    /// Translation task; `src_lang` and `tgt_lang` are the model's language codes
+    /// (FLORES-200 codes such as `eng_Latn` for NLLB)
+    pub async fn translate(
+        &self,
+        model: &str,
+        text: &str,
+        src_lang: &str,
+        tgt_lang: &str,
+    ) -> Result<String> {
+        let url = format!("{}/models/{}", self.api_base, model);
+
+        let payload = json!({
+            "inputs": text,
+            "parameters": {
+                "src_lang": src_lang,
+                "tgt_lang": tgt_lang,
+            }
+        });
+
+        let max_retries = 3;
+        let mut last_error = None;
+
+        for attempt in 1..=max_retries {
+            tracing::debug!("Translation attempt {} for model: {}", attempt, model);
+
+            match self
+                .client
+                .post(&url)
+                .header("Authorization", format!("Bearer {}", self.token))
+                .json(&payload)
+                .timeout(std::time::Duration::from_secs(90))
+                .send()
+                .await
+            {
+                Ok(response) => {
+                    let status = response.status();
+
+                    if status.is_success() {
+                        match response.json::<Vec<TranslationResponse>>().await {
+                            Ok(result) => match result.into_iter().next() {
+                                Some(first) if !first.translation_text.trim().is_empty() => {
+                                    return Ok(first.translation_text.trim().to_string());
+                                }
+                                _ => {
+                                    last_error = Some(AppError::ExternalApi(
+                                        "No translation in HuggingFace response".to_string(),
+                                    ));
+                                }
+                            },
+                            Err(e) => {
+                                last_error = Some(AppError::ExternalApi(format!(
+                                    "Failed to parse translation response: {}",
+                                    e
+                                )));
+                            }
+                        }
+                    } else {
+                        let error_text = response.text().await.unwrap_or_default();
+                        last_error = Some(AppError::ExternalApi(format!(
+                            "HuggingFace Translation error {}: {}",
+                            status, error_text
+                        )));
+
+                        // Don't retry on authentication errors
+                        if status == 401 || status == 403 {
+                            return Err(last_error.unwrap());
+                        }
+                    }
+                }
+                Err(e) => {
+                    last_error = Some(AppError::ExternalApi(format!(
+                        "Failed to connect to HuggingFace Translation API: {}",
+                        e
+                    )));
+                }
+            }
+
+            if attempt < max_retries {
+                let delay = std::time::Duration::from_millis(2000 * attempt as u64);
+                tracing::warn!(
+                    "Translation attempt {} failed, retrying in {:?}",
+                    attempt,
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| AppError::ExternalApi("Unknown translation error".to_string())))
+    }
+
     /// BART-specific summarization with better parameters
     pub async fn summarize_bart(
         &self,
@@ -436,3 +530,8 @@ struct InferenceResponse {
 struct SummarizationResponse {
     summary_text: String,
 }
+
+#[derive(Debug, Deserialize)]
+struct TranslationResponse {
+    translation_text: String,
+}
@@ -1,9 +1,13 @@
 pub mod client;
 pub mod nlp;
+pub mod structured;
 pub mod summarizer;
+pub mod translator;
 pub mod tts;
 
 pub use client::HuggingFaceClient;
 pub use nlp::NLPService;
-pub use summarizer::SummarizerService;
+pub use structured::StructuredSummaryService;
+pub use summarizer::{SummarizerService, SummaryProgress};
+pub use translator::TranslatorService;
 pub use tts::TTSService;
@@ -1,24 +1,26 @@
+use std::sync::Arc;
+
 use crate::{
     models::{ExtractedTerms, QueryIntent},
-    services::huggingface::client::HuggingFaceClient,
+    services::inference::InferenceBackend,
     utils::errors::Result,
 };
 
 const NLP_MODEL: &str = "mistralai/Mistral-7B-Instruct-v0.2";
 
 pub struct NLPService {
-    client: HuggingFaceClient,
+    backend: Arc<dyn InferenceBackend>,
 }
 
 impl NLPService {
-    pub fn new(client: HuggingFaceClient) -> Self {
-        Self { client }
+    pub fn new(backend: Arc<dyn InferenceBackend>) -> Self {
+        Self { backend }
     }
 
     pub async fn understand_query(&self, query: &str) -> Result<QueryIntent> {
         let prompt = self.build_nlp_prompt(query);
 
-        let response = self.client.text_generation(NLP_MODEL, &prompt).await?;
+        let response = self.backend.generate(NLP_MODEL, &prompt).await?;
 
         self.parse_nlp_response(query, &response)
     }
@@ -0,0 +1,431 @@
+use jsonschema::JSONSchema;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use crate::{
+    config::{LanguageProfile, Languages, SectionHeadings},
+    models::StructuredSummary,
+    services::inference::InferenceBackend,
+    utils::{
+        errors::{AppError, Result},
+        extractive, text,
+    },
+};
+
+/// Words of source sentences offered to the model as quote candidates
+const QUOTE_CANDIDATE_WORDS: usize = 400;
+const MAX_ATTEMPTS: usize = 2;
+
+/// JSON Schema every structured summary must satisfy
+pub fn schema() -> Value {
+    let line = json!({ "type": "string", "minLength": 1, "pattern": "^[^\\n]+$" });
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "StructuredSummary",
+        "type": "object",
+        "additionalProperties": false,
+        "required": ["overview", "plot_arc", "characters", "themes", "quotes", "reading_level"],
+        "properties": {
+            "overview": line,
+            "plot_arc": { "type": "array", "minItems": 1, "maxItems": 12, "items": line },
+            "characters": {
+                "type": "array",
+                "maxItems": 12,
+                "items": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "required": ["name", "description"],
+                    "properties": {
+                        "name": { "type": "string", "minLength": 1, "maxLength": 100 },
+                        "description": {
+                            "type": "string",
+                            "minLength": 1,
+                            "maxLength": 300,
+                            "pattern": "^[^\\n]+$"
+                        }
+                    }
+                }
+            },
+            "themes": { "type": "array", "minItems": 1, "maxItems": 10, "items": line },
+            "quotes": { "type": "array", "maxItems": 8, "items": line },
+            "reading_level": line
+        }
+    })
+}
+
+pub struct StructuredSummaryService {
+    backend: Arc<dyn InferenceBackend>,
+    model: String,
+    languages: Arc<Languages>,
+    schema: JSONSchema,
+}
+
+impl StructuredSummaryService {
+    pub fn new(
+        backend: Arc<dyn InferenceBackend>,
+        model: String,
+        languages: Arc<Languages>,
+    ) -> Self {
+        let schema = JSONSchema::compile(&schema()).expect("structured summary schema is valid");
+        Self {
+            backend,
+            model,
+            languages,
+            schema,
+        }
+    }
+
+    pub fn model(&self) -> String {
+        self.backend.text_model(&self.model)
+    }
+
+    /// Turn a finished summary into a structured summary in `language`.
+    ///
+    /// The model sees the summary and a TextRank selection of source sentences (in
+    /// `source_language`) to quote from. Its JSON is validated against `schema()`, and an
+    /// invalid answer is retried once with the validation errors. Quotes that do not
+    /// appear verbatim in `source` (ignoring line wrapping) are dropped.
+    pub async fn structure(
+        &self,
+        summary: &str,
+        source: &str,
+        source_language: &str,
+        language: &str,
+    ) -> Result<StructuredSummary> {
+        let profile = self.languages.resolve(language)?;
+        let source_profile = self.languages.resolve(source_language)?;
+        let source = text::strip_gutenberg_boilerplate(source);
+        let candidates = quote_candidates(&source, source_profile);
+
+        let mut last_error: Option<String> = None;
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            let prompt = build_prompt(summary, &candidates, profile, last_error.as_deref());
+            let response = self.backend.generate(&self.model, &prompt).await?;
+            match self.parse(&response) {
+                Ok(mut structured) => {
+                    keep_verbatim_quotes(&mut structured, &source);
+                    return Ok(structured);
+                }
+                Err(errors) => {
+                    tracing::warn!(
+                        "Structured summary attempt {} was invalid: {}",
+                        attempt,
+                        errors.join("; ")
+                    );
+                    last_error = Some(errors.join("; "));
+                }
+            }
+        }
+
+        Err(AppError::ServiceError(format!(
+            "Model did not produce a valid structured summary: {}",
+            last_error.unwrap_or_default()
+        )))
+    }
+
+    /// Plain-text rendering with headings in `language`, stored as `structured_text` and
+    /// read aloud for audio; `summary_text` keeps the prose summary it was built from.
+    ///
+    /// Headings and list items end with a full stop so speech pauses between them.
+    pub fn render(&self, structured: &StructuredSummary, language: &str) -> Result<String> {
+        let headings: &SectionHeadings = self.languages.resolve(language)?.headings;
+        let mut sections = vec![format!(
+            "{}.\n{}",
+            headings.overview,
+            sentence(&structured.overview)
+        )];
+
+        sections.push(section(
+            headings.plot,
+            structured.plot_arc.iter().map(|point| sentence(point)),
+        ));
+        if !structured.characters.is_empty() {
+            sections.push(section(
+                headings.characters,
+                structured.characters.iter().map(|character| {
+                    format!("{}: {}", character.name, sentence(&character.description))
+                }),
+            ));
+        }
+        sections.push(section(
+            headings.themes,
+            structured.themes.iter().map(|theme| sentence(theme)),
+        ));
+        if !structured.quotes.is_empty() {
+            sections.push(section(
+                headings.quotes,
+                structured
+                    .quotes
+                    .iter()
+                    .map(|quote| format!("\u{201C}{}\u{201D}", quote)),
+            ));
+        }
+        sections.push(format!(
+            "{}.\n{}",
+            headings.reading_level,
+            sentence(&structured.reading_level)
+        ));
+
+        Ok(sections.join("\n\n"))
+    }
+
+    /// The model's JSON as a `StructuredSummary`, or the schema violations found
+    fn parse(&self, response: &str) -> std::result::Result<StructuredSummary, Vec<String>> {
+        let start = response.find('{');
+        let end = response.rfind('}');
+        let json = match (start, end) {
+            (Some(start), Some(end)) if start < end => &response[start..=end],
+            _ => return Err(vec!["no JSON object in the answer".to_string()]),
+        };
+
+        let mut value: Value =
+            serde_json::from_str(json).map_err(|e| vec![format!("invalid JSON: {}", e)])?;
+        collapse_whitespace(&mut value);
+
+        if let Err(errors) = self.schema.validate(&value) {
+            return Err(errors
+                .map(|error| {
+                    let path = error.instance_path.to_string();
+                    if path.is_empty() {
+                        error.to_string()
+                    } else {
+                        format!("{}: {}", path, error)
+                    }
+                })
+                .collect());
+        }
+
+        serde_json::from_value(value).map_err(|e| vec![e.to_string()])
+    }
+}
+
+/// Instruction for the model; `rejection` explains why its previous answer was refused
+fn build_prompt(
+    summary: &str,
+    candidates: &[String],
+    language: &LanguageProfile,
+    rejection: Option<&str>,
+) -> String {
+    let passages = candidates
+        .iter()
+        .enumerate()
+        .map(|(i, passage)| format!("{}. {}", i + 1, passage))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let retry = rejection
+        .map(|errors| {
+            format!(
+                "\n\nYour previous answer was rejected: {}. Answer again with valid JSON only.",
+                errors
+            )
+        })
+        .unwrap_or_default();
+
+    format!(
+        r#"<s>[INST] You turn book summaries into structured JSON.
+
+Write every field in {language}, except "quotes". Respond with only a JSON object of this form:
+{{"overview": "...", "plot_arc": ["...", "..."], "characters": [{{"name": "...", "description": "..."}}], "themes": ["..."], "quotes": ["..."], "reading_level": "..."}}
+
+- overview: a short paragraph on a single line
+- plot_arc: the main turns of the story (or of the argument) in order, one sentence each
+- characters: the main characters, each with a one-line description; empty if there are none
+- themes: the main themes, a few words each
+- quotes: up to 5 notable passages copied exactly, word for word, from the numbered passages below; never write your own
+- reading_level: one sentence on who the book suits and how demanding it is to read
+
+Summary:
+{summary}
+
+Passages:
+{passages}{retry}
+[/INST]"#,
+        language = language.name,
+        summary = summary,
+        passages = passages,
+        retry = retry
+    )
+}
+
+/// Representative source sentences the model may quote, picked offline with TextRank
+fn quote_candidates(source: &str, language: &LanguageProfile) -> Vec<String> {
+    let Some(extract) = extractive::summarize(
+        source,
+        QUOTE_CANDIDATE_WORDS,
+        language.sentences,
+        language.stopwords,
+    ) else {
+        return Vec::new();
+    };
+
+    text::sentence_spans(&extract.summary, language.sentences)
+        .into_iter()
+        .map(|span| extract.summary[span].to_string())
+        .collect()
+}
+
+/// Drop quotes the model did not copy from the source
+fn keep_verbatim_quotes(structured: &mut StructuredSummary, source: &str) {
+    let source = normalize(source);
+    structured.quotes.retain(|quote| {
+        let quote =
+            normalize(quote.trim_matches(|c: char| "\"'\u{201C}\u{201D}\u{201E}".contains(c)));
+        let found = !quote.is_empty() && source.contains(&quote);
+        if !found {
+            tracing::info!("Dropping quote not found verbatim in the source: {}", quote);
+        }
+        found
+    });
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Fold line breaks and runs of spaces inside every string, so each field is one line
+fn collapse_whitespace(value: &mut Value) {
+    match value {
+        Value::String(s) => *s = normalize(s),
+        Value::Array(items) => items.iter_mut().for_each(collapse_whitespace),
+        Value::Object(fields) => fields.values_mut().for_each(collapse_whitespace),
+        _ => {}
+    }
+}
+
+fn sentence(text: &str) -> String {
+    if text.ends_with(['.', '!', '?', '…']) {
+        text.to_string()
+    } else {
+        format!("{}.", text)
+    }
+}
+
+fn section(heading: &str, items: impl Iterator<Item = String>) -> String {
+    let items: Vec<String> = items.collect();
+    format!("{}.\n{}", heading, items.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{huggingface::HuggingFaceClient, inference::HuggingFaceBackend};
+    use std::collections::HashMap;
+
+    fn service() -> StructuredSummaryService {
+        let backend = HuggingFaceBackend::new(HuggingFaceClient::new(
+            reqwest::Client::new(),
+            "http://localhost".to_string(),
+            String::new(),
+        ));
+        let languages = Languages::new(&["en".to_string()], &HashMap::new(), &HashMap::new());
+        StructuredSummaryService::new(
+            Arc::new(backend),
+            "test-model".to_string(),
+            Arc::new(languages),
+        )
+    }
+
+    fn valid() -> Value {
+        json!({
+            "overview": "A whale hunt told by a sailor",
+            "plot_arc": ["Ishmael signs on", "Ahab hunts the whale"],
+            "characters": [{ "name": "Ahab", "description": "The obsessed captain" }],
+            "themes": ["Obsession"],
+            "quotes": ["Call me Ishmael."],
+            "reading_level": "Demanding, for adult readers"
+        })
+    }
+
+    fn structured() -> StructuredSummary {
+        serde_json::from_value(valid()).unwrap()
+    }
+
+    #[test]
+    fn schema_compiles() {
+        assert!(JSONSchema::compile(&schema()).is_ok());
+    }
+
+    #[test]
+    fn parse_accepts_json_wrapped_in_prose() {
+        let response = format!("Here is the summary:\n{}\nHope this helps!", valid());
+        let parsed = service().parse(&response).unwrap();
+        assert_eq!(parsed.overview, "A whale hunt told by a sailor");
+        assert_eq!(parsed.characters[0].name, "Ahab");
+        assert_eq!(parsed.quotes, vec!["Call me Ishmael."]);
+    }
+
+    #[test]
+    fn parse_folds_line_breaks_inside_fields() {
+        let mut value = valid();
+        value["overview"] = json!("A whale hunt\ntold by   a sailor");
+        let parsed = service().parse(&value.to_string()).unwrap();
+        assert_eq!(parsed.overview, "A whale hunt told by a sailor");
+    }
+
+    #[test]
+    fn parse_rejects_answers_without_json() {
+        let errors = service()
+            .parse("I cannot summarize this book.")
+            .unwrap_err();
+        assert_eq!(errors, vec!["no JSON object in the answer"]);
+        assert!(service().parse("{ not json }").is_err());
+    }
+
+    #[test]
+    fn parse_rejects_schema_violations() {
+        let service = service();
+
+        let mut missing = valid();
+        missing.as_object_mut().unwrap().remove("themes");
+        assert!(service.parse(&missing.to_string()).is_err());
+
+        let mut extra = valid();
+        extra["rating"] = json!(5);
+        assert!(service.parse(&extra.to_string()).is_err());
+
+        let mut empty_plot = valid();
+        empty_plot["plot_arc"] = json!([]);
+        let errors = service.parse(&empty_plot.to_string()).unwrap_err();
+        assert!(errors.iter().any(|error| error.starts_with("/plot_arc")));
+
+        let mut blank_overview = valid();
+        blank_overview["overview"] = json!("");
+        assert!(service.parse(&blank_overview.to_string()).is_err());
+
+        let mut long_description = valid();
+        long_description["characters"][0]["description"] = json!("x".repeat(301));
+        assert!(service.parse(&long_description.to_string()).is_err());
+    }
+
+    #[test]
+    fn render_lays_out_sections_with_headings() {
+        let rendered = service().render(&structured(), "en").unwrap();
+        assert_eq!(
+            rendered,
+            "Overview.\nA whale hunt told by a sailor.\n\n\
+             Plot.\nIshmael signs on.\nAhab hunts the whale.\n\n\
+             Main characters.\nAhab: The obsessed captain.\n\n\
+             Themes.\nObsession.\n\n\
+             Notable quotes.\n\u{201C}Call me Ishmael.\u{201D}\n\n\
+             Reading level.\nDemanding, for adult readers."
+        );
+    }
+
+    #[test]
+    fn render_omits_empty_characters_and_quotes() {
+        let mut summary = structured();
+        summary.characters.clear();
+        summary.quotes.clear();
+        let rendered = service().render(&summary, "en").unwrap();
+        assert!(!rendered.contains("Main characters"));
+        assert!(!rendered.contains("Notable quotes"));
+        assert!(rendered.starts_with("Overview.\n"));
+        assert!(rendered.ends_with("Reading level.\nDemanding, for adult readers."));
+    }
+
+    #[test]
+    fn render_rejects_unknown_languages() {
+        assert!(service().render(&structured(), "xx").is_err());
+    }
+}
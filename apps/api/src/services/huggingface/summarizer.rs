@@ -1,218 +1,937 @@
-use crate::{services::huggingface::client::HuggingFaceClient, utils::errors::Result};
+use futures::{stream, StreamExt};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use tokio::sync::mpsc::UnboundedSender;
 
-// Switch to BART for better book summarization
-const SUMMARIZATION_MODEL: &str = "facebook/bart-large-cnn";
+use crate::{
+    config::{LanguageProfile, Languages},
+    models::SummaryMethod,
+    services::{
+        inference::{InferenceBackend, SummarizeParams},
+        storage::DatabaseService,
+    },
+    utils::{
+        chapters,
+        errors::{AppError, Result},
+        extractive, text,
+    },
+};
 
-struct StyleParameters {
-    target_tokens: usize,
-    min_tokens: usize,
+// Words per model call; leaves room for the style instruction in BART's 1024-token window
+const MAX_INPUT_WORDS: usize = 600;
+/// Rough model tokens per word, used to turn word budgets into generation limits
+const TOKENS_PER_WORD: f64 = 1.4;
+/// A summary is never asked to be longer than this share of the text it condenses
+const MAX_SOURCE_SHARE: f64 = 0.5;
+// Chunk and merge summaries stay within these bounds however the budget divides
+const MIN_CHUNK_WORDS: usize = 40;
+const MAX_CHUNK_WORDS: usize = 220;
+/// Sections are not split finer than this when a short summary is extended
+const MIN_SECTION_WORDS: usize = 80;
+/// Most tokens one call is asked to write; BART degrades on outputs this long already,
+/// so longer budgets are written in several sections
+const MAX_OUTPUT_TOKENS: usize = 512;
+
+fn word_count(texts: &[String]) -> usize {
+    texts.iter().map(|t| t.split_whitespace().count()).sum()
+}
+
+fn tokens(words: usize) -> usize {
+    (words as f64 * TOKENS_PER_WORD).ceil() as usize
+}
+
+/// Storage key of a chunk summary; the length it was asked for is part of it.
+///
+/// Chunk lengths follow the style and page budget (see `Budget::chunk_params`), and
+/// chunks are summarized with the style's instruction, so stored summaries only serve
+/// requests with the same style and budget. A summary in another style or length runs
+/// its chunks again.
+fn chunk_hash(text: &str, params: SummarizeParams) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}-{}:", params.min_tokens, params.max_tokens).as_bytes());
+    hasher.update(text.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Language and style a summary is written in
+#[derive(Clone, Copy)]
+struct Target<'a> {
+    language: &'a LanguageProfile,
+    style: &'a str,
+}
+
+/// Word targets for a summary, derived from the requested number of pages
+#[derive(Debug, Clone, Copy)]
+struct Budget {
+    pages: usize,
+    /// The requested pages filled completely; never exceeded
+    max_words: usize,
+    target_words: usize,
+    /// Shorter summaries are extended when the source allows it
+    min_words: usize,
+}
+
+impl Budget {
+    /// `max_pages` pages of `words_per_page` words; shorter styles aim for part of that
+    fn new(max_pages: usize, words_per_page: usize, style: &str) -> Self {
+        let max_words = max_pages.max(1) * words_per_page.max(1);
+        let share = match style {
+            "detailed" => 1.0,
+            "academic" => 0.85,
+            "simple" => 0.65,
+            _ => 0.55, // concise
+        };
+        let budget = Self {
+            pages: max_pages.max(1),
+            max_words,
+            target_words: max_words,
+            min_words: max_words,
+        };
+        budget.resized((max_words as f64 * share) as usize, max_words)
+    }
+
+    fn resized(self, target_words: usize, max_words: usize) -> Self {
+        let target_words = target_words.clamp(1, max_words.max(1));
+        Self {
+            pages: self.pages,
+            max_words: max_words.max(1),
+            target_words,
+            min_words: (target_words * 7 / 10).max(1),
+        }
+    }
+
+    /// The budget scaled down so the summary stays well short of its `source_words`
+    fn for_source(self, source_words: usize) -> Self {
+        let limit = (source_words as f64 * MAX_SOURCE_SHARE) as usize;
+        if self.target_words <= limit {
+            return self;
+        }
+        self.resized(limit, self.max_words.min(source_words))
+    }
+
+    /// Budget for one of `parts` sections written separately and joined
+    fn share(self, parts: usize) -> Self {
+        let parts = parts.max(1);
+        self.resized(self.target_words / parts, self.max_words / parts)
+    }
+
+    /// Token limits for one call writing this budget, capped at the model's output limit
+    fn params(self) -> SummarizeParams {
+        let max_tokens = self.requested_tokens().min(MAX_OUTPUT_TOKENS);
+        SummarizeParams {
+            max_tokens,
+            min_tokens: tokens(self.min_words).min(max_tokens * 7 / 10),
+        }
+    }
+
+    fn requested_tokens(self) -> usize {
+        tokens((self.target_words * 5 / 4).min(self.max_words))
+    }
+
+    /// Fewest sections the budget must be written in so no call exceeds the output limit
+    fn sections(self) -> usize {
+        self.requested_tokens().div_ceil(MAX_OUTPUT_TOKENS).max(1)
+    }
+
+    /// Chunk and merge summaries get a share of twice the budget, so the material that
+    /// reaches the final summary is enough to fill it
+    fn chunk_params(self, chunks: usize) -> SummarizeParams {
+        let words = (self.max_words * 2 / chunks.max(1)).clamp(MIN_CHUNK_WORDS, MAX_CHUNK_WORDS);
+        SummarizeParams {
+            max_tokens: tokens(words),
+            min_tokens: tokens(words / 4),
+        }
+    }
+
+    /// Most words the merged summaries may hold before the final summary is written
+    fn material_words(self) -> usize {
+        MAX_INPUT_WORDS.max(self.max_words * 2)
+    }
+
+    fn contains(self, words: usize) -> bool {
+        (self.min_words..=self.max_words).contains(&words)
+    }
+}
+
+/// How the final summary was brought within its budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LengthFit {
+    /// Came out within the budget
+    Within,
+    /// Was too long and summarized again
+    Recompressed,
+    /// Was too long and cut back to whole sentences
+    Trimmed,
+    /// Was too short and rewritten from more, smaller sections
+    Extended,
+    /// Is shorter than the budget and the source offers nothing more
+    Short,
+}
+
+impl LengthFit {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LengthFit::Within => "within",
+            LengthFit::Recompressed => "recompressed",
+            LengthFit::Trimmed => "trimmed",
+            LengthFit::Extended => "extended",
+            LengthFit::Short => "short",
+        }
+    }
+}
+
+/// Final summary text with the number of sections it was written in
+struct Written {
+    text: String,
+    sections: usize,
+}
+
+/// Pipeline stage reported while a summary is being produced
+#[derive(Debug, Clone)]
+pub enum SummaryProgress {
+    Chunking,
+    Chunked {
+        chunks: usize,
+    },
+    Summarizing,
+    ChunkSummarized {
+        index: usize,
+        total: usize,
+        summary: String,
+    },
+}
+
+pub type ProgressSender = UnboundedSender<SummaryProgress>;
+
+/// A chunk to summarize, with its storage key and any summary kept from an earlier run
+struct StoredChunk<'a> {
+    text: &'a str,
+    hash: String,
+    model: &'a str,
+    existing: Option<String>,
+}
+
+/// Overall summary of a book plus one summary per detected chapter, with how it was made
+#[derive(Debug, Clone)]
+pub struct BookSummary {
+    pub summary: String,
+    pub method: SummaryMethod,
+    pub chapters: Vec<ChapterSummary>,
+    /// Model that wrote the final summary ("textrank" for extractive summaries)
+    pub model: String,
+    pub parameters: serde_json::Value,
+    /// Pieces the input was split into; 1 when it was summarized directly
+    pub chunk_count: usize,
+    /// Part of the input never reached the summary (failed chunks or merges, sampled sentences)
+    pub truncated: bool,
+}
+
+impl BookSummary {
+    /// Record how the final summary was written and fitted to its budget
+    fn with_final(mut self, sections: usize, fit: LengthFit) -> Self {
+        let words = self.summary.split_whitespace().count();
+        self.parameters["final_sections"] = json!(sections);
+        self.parameters["words"] = json!(words);
+        self.parameters["length_fit"] = json!(fit.as_str());
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChapterSummary {
+    pub title: String,
+    pub summary: String,
 }
 
 pub struct SummarizerService {
-    client: HuggingFaceClient,
+    backend: Arc<dyn InferenceBackend>,
+    db: DatabaseService,
+    languages: Arc<Languages>,
+    chunk_concurrency: usize,
+    words_per_page: usize,
 }
 
 impl SummarizerService {
-    pub fn new(client: HuggingFaceClient) -> Self {
-        Self { client }
+    pub fn new(
+        backend: Arc<dyn InferenceBackend>,
+        db: DatabaseService,
+        languages: Arc<Languages>,
+        chunk_concurrency: usize,
+        words_per_page: usize,
+    ) -> Self {
+        Self {
+            backend,
+            db,
+            languages,
+            chunk_concurrency: chunk_concurrency.max(1),
+            words_per_page: words_per_page.max(1),
+        }
     }
 
     /// Main entry point for book summarization
-    /// Implements fast summarization optimized for speed
-    pub async fn summarize(&self, content: &str, language: &str, style: &str) -> Result<String> {
-        if content.is_empty() {
-            return Ok(self.get_fallback_message(language));
+    /// Map-reduce over the whole text: every chunk is summarized, then merged level by level.
+    /// The model, style instructions and sentence rules come from `language`'s profile, and
+    /// the length from `max_pages` pages of the configured words per page.
+    pub async fn summarize(
+        &self,
+        content: &str,
+        language: &str,
+        style: &str,
+        max_pages: usize,
+        progress: Option<&ProgressSender>,
+    ) -> Result<BookSummary> {
+        if content.trim().is_empty() {
+            return Err(AppError::InvalidInput(
+                "No content available for summarization".to_string(),
+            ));
         }
 
+        let target = Target {
+            language: self.languages.resolve(language)?,
+            style,
+        };
+        let model = target.language.summarization_model.as_str();
+        let budget = Budget::new(max_pages, self.words_per_page, style);
+
         tracing::info!(
-            "Starting fast summarization for language: {} with style: {}",
+            "Starting summarization for language: {} with style: {} using {}, aiming for {} words ({} pages)",
             language,
-            style
+            style,
+            model,
+            budget.target_words,
+            max_pages
         );
 
         // Step 1: Clean the content
-        let cleaned_content = self.clean_project_gutenberg_text(content);
+        let cleaned_content = text::strip_gutenberg_boilerplate(content);
         let word_count = cleaned_content.split_whitespace().count();
 
         tracing::info!("Cleaned content has {} words", word_count);
 
         // For shorter content, skip chunking and summarize directly
-        if word_count <= 2000 {
+        if word_count <= MAX_INPUT_WORDS {
             tracing::info!("Content is short enough for direct summarization");
-            let style_params = self.get_style_parameters(style);
-            tracing::info!(
-                "Using style '{}' with {} target tokens, {} min tokens",
-                style,
-                style_params.target_tokens,
-                style_params.min_tokens
-            );
-            let styled_content = self.add_style_instruction(&cleaned_content, style);
-            return self
-                .client
-                .summarize_bart(
-                    SUMMARIZATION_MODEL,
-                    &styled_content,
-                    style_params.target_tokens,
-                    style_params.min_tokens,
-                )
-                .await;
+            self.report(progress, SummaryProgress::Summarizing);
+            let budget = budget.for_source(word_count);
+            return match self
+                .write_sections(&cleaned_content, MAX_INPUT_WORDS, target, budget)
+                .await
+            {
+                Ok(written) => {
+                    let (summary, fit) = self
+                        .fit_to_budget(written.text, &cleaned_content, target, budget)
+                        .await;
+                    Ok(self
+                        .abstractive_summary(summary, target, budget, None, 1, false)
+                        .with_final(written.sections, fit))
+                }
+                Err(e) => {
+                    tracing::warn!("Direct summarization failed: {}", e);
+                    self.extractive_summary(&cleaned_content, target, budget, 1, false)
+                }
+            };
         }
 
-        // Step 2: Smart chunking with larger chunks for efficiency
-        let chunks = self.smart_chunk_by_paragraphs(&cleaned_content, 1500);
+        // Step 2: Split into chapters when headings are found, then chunk each section
+        self.report(progress, SummaryProgress::Chunking);
+        let chapters = chapters::segment_chapters(&cleaned_content);
+        let sections: Vec<(Option<String>, Vec<String>)> = if chapters.is_empty() {
+            vec![(
+                None,
+                self.smart_chunk_by_paragraphs(&cleaned_content, MAX_INPUT_WORDS, target),
+            )]
+        } else {
+            tracing::info!("Found {} chapters", chapters.len());
+            chapters
+                .into_iter()
+                .map(|chapter| {
+                    let chunks =
+                        self.smart_chunk_by_paragraphs(&chapter.content, MAX_INPUT_WORDS, target);
+                    (Some(chapter.title), chunks)
+                })
+                .collect()
+        };
+        let chunks: Vec<String> = sections
+            .iter()
+            .flat_map(|(_, chunks)| chunks.iter().cloned())
+            .collect();
 
         if chunks.is_empty() {
-            return Ok(self.fallback_summary(&cleaned_content, style));
+            return self.extractive_summary(&cleaned_content, target, budget, 0, false);
         }
 
         tracing::info!("Split into {} chunks for processing", chunks.len());
+        self.report(
+            progress,
+            SummaryProgress::Chunked {
+                chunks: chunks.len(),
+            },
+        );
+        self.report(progress, SummaryProgress::Summarizing);
 
-        // Step 3: Summarize chunks directly to final summary (skip intermediate steps)
-        let chunk_summaries = self.summarize_chunks(&chunks, language, style).await?;
+        // Step 3: Map - summarize every chunk
+        let chunk_params = budget.chunk_params(chunks.len());
+        let chunk_summaries = self
+            .summarize_chunks(&chunks, target, chunk_params, progress)
+            .await;
+        let summarized = chunk_summaries.iter().flatten().count();
 
-        if chunk_summaries.is_empty() {
-            return Ok(self.fallback_summary(&cleaned_content, style));
+        if summarized == 0 {
+            tracing::warn!("Every chunk failed to summarize");
+            return self.extractive_summary(&cleaned_content, target, budget, chunks.len(), false);
         }
 
-        tracing::info!("Generated {} chunk summaries", chunk_summaries.len());
+        tracing::info!(
+            "Generated {} of {} chunk summaries",
+            summarized,
+            chunks.len()
+        );
+
+        // Set whenever a failed merge has to fall back to cutting its input short
+        let truncated = AtomicBool::new(summarized < chunks.len());
 
-        // Step 4: Create final summary directly from chunk summaries
-        let combined_summaries = chunk_summaries.join("\n\n");
-        let style_params = self.get_style_parameters(style);
+        // Step 4: Condense each chapter to one summary; unchaptered text goes straight through
+        let mut chunk_summaries = chunk_summaries.into_iter();
+        let mut section_summaries = Vec::new();
+        let mut chapter_tasks = Vec::new();
+        for (title, section_chunks) in sections {
+            let summaries: Vec<String> = chunk_summaries
+                .by_ref()
+                .take(section_chunks.len())
+                .flatten()
+                .collect();
+            match title {
+                Some(title) => chapter_tasks.push(self.summarize_chapter(
+                    title,
+                    summaries,
+                    target,
+                    chunk_params,
+                    &truncated,
+                )),
+                None => section_summaries.extend(summaries),
+            }
+        }
+
+        let chapter_summaries: Vec<ChapterSummary> = stream::iter(chapter_tasks)
+            .buffered(self.chunk_concurrency)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+        section_summaries.extend(chapter_summaries.iter().map(|c| c.summary.clone()));
+
+        // Step 5: Reduce - merge summaries level by level until there is about enough
+        // material left to fill the budget
+        let merged_summaries = self
+            .reduce_summaries(
+                section_summaries,
+                target,
+                chunk_params,
+                budget.material_words(),
+                &truncated,
+            )
+            .await;
+
+        // Step 6: Create final summary from the merged summaries
+        let combined_summaries = merged_summaries.join("\n\n");
+        let budget = budget.for_source(combined_summaries.split_whitespace().count());
         tracing::info!(
-            "Applying final summarization with style '{}': {} target tokens, {} min tokens",
+            "Applying final summarization with style '{}': {} target words ({} - {})",
             style,
-            style_params.target_tokens,
-            style_params.min_tokens
+            budget.target_words,
+            budget.min_words,
+            budget.max_words
         );
-        let styled_content = self.add_style_instruction(&combined_summaries, style);
-        let final_summary = self
-            .client
-            .summarize_bart(
-                SUMMARIZATION_MODEL,
-                &styled_content,
-                style_params.target_tokens,
-                style_params.min_tokens,
-            )
+        let truncated = truncated.into_inner();
+        let mut book_summary = match self
+            .write_sections(&combined_summaries, MAX_INPUT_WORDS, target, budget)
             .await
-            .unwrap_or_else(|_| self.fallback_summary(&combined_summaries, style));
+        {
+            Ok(written) => {
+                let (summary, fit) = self
+                    .fit_to_budget(written.text, &combined_summaries, target, budget)
+                    .await;
+                self.abstractive_summary(
+                    summary,
+                    target,
+                    budget,
+                    Some(chunk_params),
+                    chunks.len(),
+                    truncated,
+                )
+                .with_final(written.sections, fit)
+            }
+            Err(e) => {
+                tracing::warn!("Final summarization failed: {}", e);
+                self.extractive_summary(
+                    &combined_summaries,
+                    target,
+                    budget,
+                    chunks.len(),
+                    truncated,
+                )?
+            }
+        };
+        book_summary.chapters = chapter_summaries;
 
-        let word_count = final_summary.split_whitespace().count();
         tracing::info!(
-            "Final summary generated with {} words in language: {}",
-            word_count,
+            "Final {} summary generated with {} words and {} chapter summaries in language: {}",
+            book_summary.method.as_str(),
+            book_summary.summary.split_whitespace().count(),
+            book_summary.chapters.len(),
             language
         );
 
-        Ok(final_summary)
+        Ok(book_summary)
     }
 
-    /// Summarize individual chunks (Level 1)
-    async fn summarize_chunks(
+    /// Summarize `material` in sections of at most `section_words` words that share the
+    /// budget; the section summaries are joined in order. Material that fits one section
+    /// is summarized in a single call, unless the budget is more than one call may write.
+    async fn write_sections(
         &self,
-        chunks: &[String],
-        _language: &str,
-        style: &str,
-    ) -> Result<Vec<String>> {
-        let mut summaries = Vec::new();
-
-        // Limit to prevent excessive API calls and timeouts
-        let max_chunks = 4.min(chunks.len());
-
-        for (i, chunk) in chunks.iter().take(max_chunks).enumerate() {
-            let chunk_params = self.get_chunk_style_parameters(style);
-            let styled_chunk = self.add_style_instruction(chunk, style);
-            let summary_result = self
-                .client
-                .summarize_bart(
-                    SUMMARIZATION_MODEL,
-                    &styled_chunk,
-                    chunk_params.target_tokens,
-                    chunk_params.min_tokens,
+        material: &str,
+        section_words: usize,
+        target: Target<'_>,
+        budget: Budget,
+    ) -> Result<Written> {
+        let mut sections = self.smart_chunk_by_paragraphs(material, section_words, target);
+        if sections.len() < budget.sections() {
+            let material_words = material.split_whitespace().count();
+            let section_words = (material_words / budget.sections()).max(MIN_SECTION_WORDS);
+            sections = self.smart_chunk_by_paragraphs(material, section_words, target);
+        }
+        if sections.is_empty() {
+            return Err(AppError::InvalidInput(
+                "No content available for summarization".to_string(),
+            ));
+        }
+
+        let params = budget.share(sections.len()).params();
+        let tasks: Vec<_> = sections
+            .iter()
+            .map(|section| {
+                let styled = self.add_style_instruction(section, target);
+                async move {
+                    self.backend
+                        .summarize(&target.language.summarization_model, &styled, params)
+                        .await
+                        .map(|summary| self.clean_summary(&summary))
+                }
+            })
+            .collect();
+
+        let summaries = stream::iter(tasks)
+            .buffered(self.chunk_concurrency)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<String>>>()?;
+
+        Ok(Written {
+            text: summaries.join("\n\n"),
+            sections: summaries.len(),
+        })
+    }
+
+    /// Check a final summary against its budget.
+    ///
+    /// A summary that is too long is summarized again and, if that still misses, cut back to
+    /// whole sentences. One that is too short is written again from `material` in more,
+    /// smaller sections, and kept only if that made it longer.
+    async fn fit_to_budget(
+        &self,
+        summary: String,
+        material: &str,
+        target: Target<'_>,
+        budget: Budget,
+    ) -> (String, LengthFit) {
+        let words = summary.split_whitespace().count();
+        if budget.contains(words) {
+            return (summary, LengthFit::Within);
+        }
+
+        if words > budget.max_words {
+            tracing::info!(
+                "Summary has {} words, over the {} word budget; compressing",
+                words,
+                budget.max_words
+            );
+            let styled = self.add_style_instruction(&summary, target);
+            match self
+                .backend
+                .summarize(
+                    &target.language.summarization_model,
+                    &styled,
+                    budget.params(),
                 )
-                .await;
-
-            match summary_result {
-                Ok(summary) => {
-                    let clean = self.clean_summary(&summary);
-                    if !clean.is_empty() {
-                        summaries.push(clean);
-                        tracing::debug!("Chunk {} summarized successfully", i + 1);
+                .await
+            {
+                Ok(compressed) => {
+                    let compressed = self.clean_summary(&compressed);
+                    let compressed_words = compressed.split_whitespace().count();
+                    if compressed_words > 0 && compressed_words <= budget.max_words {
+                        return (compressed, LengthFit::Recompressed);
                     }
                 }
-                Err(e) => {
-                    tracing::warn!("Failed to summarize chunk {}: {}", i + 1, e);
-                    // Continue with other chunks
-                    continue;
+                Err(e) => tracing::warn!("Failed to compress summary: {}", e),
+            }
+            let trimmed =
+                text::truncate_sentences(&summary, budget.max_words, target.language.sentences);
+            return (trimmed, LengthFit::Trimmed);
+        }
+
+        let material_words = material.split_whitespace().count();
+        let sections = budget.target_words.div_ceil(words.max(1)).max(2);
+        let section_words = material_words / sections;
+        if section_words < MIN_SECTION_WORDS {
+            tracing::info!(
+                "Summary has {} words, under the {} word budget, and the source is too short to extend it",
+                words,
+                budget.min_words
+            );
+            return (summary, LengthFit::Short);
+        }
+
+        tracing::info!(
+            "Summary has {} words, under the {} word budget; rewriting in {} sections",
+            words,
+            budget.min_words,
+            sections
+        );
+        match self
+            .write_sections(material, section_words, target, budget)
+            .await
+        {
+            Ok(extended) => {
+                let extended_words = extended.text.split_whitespace().count();
+                if extended_words > budget.max_words {
+                    let trimmed = text::truncate_sentences(
+                        &extended.text,
+                        budget.max_words,
+                        target.language.sentences,
+                    );
+                    (trimmed, LengthFit::Extended)
+                } else if extended_words > words {
+                    (extended.text, LengthFit::Extended)
+                } else {
+                    (summary, LengthFit::Short)
                 }
             }
+            Err(e) => {
+                tracing::warn!("Failed to extend summary: {}", e);
+                (summary, LengthFit::Short)
+            }
+        }
+    }
+
+    /// Condense the chunk summaries of one chapter into a single chapter summary
+    async fn summarize_chapter(
+        &self,
+        title: String,
+        summaries: Vec<String>,
+        target: Target<'_>,
+        params: SummarizeParams,
+        truncated: &AtomicBool,
+    ) -> Option<ChapterSummary> {
+        if summaries.is_empty() {
+            tracing::warn!("No chunk summaries for chapter '{}', skipping", title);
+            return None;
         }
 
-        Ok(summaries)
+        let mut merged = self
+            .reduce_summaries(summaries, target, params, MAX_INPUT_WORDS, truncated)
+            .await;
+        let summary = if merged.len() == 1 {
+            merged.remove(0)
+        } else {
+            self.merge_group(merged, target, params, truncated).await
+        };
+
+        if summary.is_empty() {
+            return None;
+        }
+
+        Some(ChapterSummary { title, summary })
     }
 
-    fn get_fallback_message(&self, _language: &str) -> String {
-        "No content available for summarization.".to_string()
+    /// Summarize every chunk (level 1), at most `chunk_concurrency` at a time.
+    /// Chunks summarized by an earlier run with the same model, style, language and
+    /// chunk length are reused.
+    /// Results keep the chunk order; chunks that fail are `None`.
+    async fn summarize_chunks(
+        &self,
+        chunks: &[String],
+        target: Target<'_>,
+        chunk_params: SummarizeParams,
+        progress: Option<&ProgressSender>,
+    ) -> Vec<Option<String>> {
+        let total = chunks.len();
+        let model = self
+            .backend
+            .text_model(&target.language.summarization_model);
+
+        let hashes: Vec<String> = chunks
+            .iter()
+            .map(|chunk| chunk_hash(chunk, chunk_params))
+            .collect();
+        let mut stored: HashMap<String, String> = match self
+            .db
+            .get_chunk_summaries(&hashes, &model, target.style, target.language.code)
+            .await
+        {
+            Ok(records) => records
+                .into_iter()
+                .map(|r| (r.chunk_hash, r.summary_text))
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Failed to load stored chunk summaries: {}", e);
+                HashMap::new()
+            }
+        };
+
+        if !stored.is_empty() {
+            tracing::info!(
+                "Reusing {} stored chunk summaries of {}",
+                stored.len(),
+                total
+            );
+        }
+
+        // Futures are built up front; mapping lazily inside the stream trips Send inference
+        let tasks: Vec<_> = chunks
+            .iter()
+            .zip(hashes)
+            .enumerate()
+            .map(|(i, (chunk, hash))| {
+                let existing = stored.remove(&hash);
+                let chunk = StoredChunk {
+                    text: chunk,
+                    hash,
+                    model: &model,
+                    existing,
+                };
+                self.summarize_chunk(chunk, i, total, target, chunk_params, progress)
+            })
+            .collect();
+
+        stream::iter(tasks)
+            .buffered(self.chunk_concurrency)
+            .collect()
+            .await
     }
 
-    fn get_style_parameters(&self, style: &str) -> StyleParameters {
-        match style {
-            "detailed" => StyleParameters {
-                target_tokens: 1500,
-                min_tokens: 400,
-            },
-            "academic" => StyleParameters {
-                target_tokens: 1300,
-                min_tokens: 350,
-            },
-            "simple" => StyleParameters {
-                target_tokens: 1000,
-                min_tokens: 250,
-            },
-            _ => StyleParameters {
-                // concise
-                target_tokens: 800,
-                min_tokens: 200,
-            },
+    async fn summarize_chunk(
+        &self,
+        chunk: StoredChunk<'_>,
+        index: usize,
+        total: usize,
+        target: Target<'_>,
+        params: SummarizeParams,
+        progress: Option<&ProgressSender>,
+    ) -> Option<String> {
+        let summary = match chunk.existing {
+            Some(summary) => Ok(summary),
+            None => {
+                let styled_chunk = self.add_style_instruction(chunk.text, target);
+                let result = self
+                    .backend
+                    .summarize(&target.language.summarization_model, &styled_chunk, params)
+                    .await
+                    .map(|summary| self.clean_summary(&summary));
+                if let Ok(clean) = &result {
+                    self.store_summary(&chunk.hash, chunk.model, target, clean)
+                        .await;
+                }
+                result
+            }
+        };
+
+        match summary {
+            Ok(clean) => {
+                if clean.is_empty() {
+                    return None;
+                }
+                tracing::debug!("Chunk {}/{} summarized successfully", index + 1, total);
+                self.report(
+                    progress,
+                    SummaryProgress::ChunkSummarized {
+                        index,
+                        total,
+                        summary: clean.clone(),
+                    },
+                );
+                Some(clean)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to summarize chunk {}/{}: {}", index + 1, total, e);
+                None
+            }
         }
     }
 
-    fn get_chunk_style_parameters(&self, style: &str) -> StyleParameters {
-        match style {
-            "detailed" => StyleParameters {
-                target_tokens: 300,
-                min_tokens: 80,
-            },
-            "academic" => StyleParameters {
-                target_tokens: 250,
-                min_tokens: 70,
-            },
-            "simple" => StyleParameters {
-                target_tokens: 180,
-                min_tokens: 50,
-            },
-            _ => StyleParameters {
-                // concise
-                target_tokens: 150,
-                min_tokens: 40,
-            },
+    /// Merge neighbouring summaries into groups that fit the model input and summarize
+    /// each group, repeating until they hold no more than `max_words` words in total
+    async fn reduce_summaries(
+        &self,
+        mut summaries: Vec<String>,
+        target: Target<'_>,
+        chunk_params: SummarizeParams,
+        max_words: usize,
+        truncated: &AtomicBool,
+    ) -> Vec<String> {
+        let mut level = 2;
+
+        while summaries.len() > 1 && word_count(&summaries) > max_words {
+            let groups = self.group_by_words(summaries, MAX_INPUT_WORDS);
+            tracing::info!(
+                "Merging summaries at level {}: {} groups",
+                level,
+                groups.len()
+            );
+
+            let tasks: Vec<_> = groups
+                .into_iter()
+                .map(|group| self.merge_group(group, target, chunk_params, truncated))
+                .collect();
+
+            summaries = stream::iter(tasks)
+                .buffered(self.chunk_concurrency)
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .filter(|summary| !summary.is_empty())
+                .collect();
+
+            level += 1;
         }
+
+        summaries
     }
 
-    fn add_style_instruction(&self, text: &str, style: &str) -> String {
-        let instruction = match style {
-            "detailed" => "INSTRUCTION: Write a comprehensive, in-depth summary that covers all major themes, character development, plot points, literary devices, and contextual significance. Include specific examples, quotes, and detailed analysis. Aim for thorough coverage with rich descriptions and explanations. Use sophisticated vocabulary and complex sentence structures:",
-            "academic" => "INSTRUCTION: Compose a formal, scholarly analysis in academic style. Use formal language, analytical frameworks, critical theory perspectives, and structured argumentation. Include discussion of literary merit, historical context, thematic significance, and scholarly interpretations. Maintain objective, analytical tone throughout:",
-            "simple" => "INSTRUCTION: Write a clear, straightforward summary using simple words and short sentences. Explain everything in an easy-to-understand way, as if writing for someone who is new to reading literature. Avoid complex vocabulary and focus on basic plot and main ideas:",
-            _ => "INSTRUCTION: Create a brief, focused summary that captures only the most essential plot points and main themes. Be direct and concise, highlighting key events and core messages without unnecessary detail:", // concise
-        };
+    async fn merge_group(
+        &self,
+        group: Vec<String>,
+        target: Target<'_>,
+        params: SummarizeParams,
+        truncated: &AtomicBool,
+    ) -> String {
+        let combined = group.join("\n\n");
+        let hash = chunk_hash(&combined, params);
+        let model = self
+            .backend
+            .text_model(&target.language.summarization_model);
+
+        match self
+            .db
+            .get_chunk_summaries(
+                std::slice::from_ref(&hash),
+                &model,
+                target.style,
+                target.language.code,
+            )
+            .await
+        {
+            Ok(mut records) if !records.is_empty() => return records.remove(0).summary_text,
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to load stored merge summary: {}", e),
+        }
+
+        let styled = self.add_style_instruction(&combined, target);
+        match self
+            .backend
+            .summarize(&target.language.summarization_model, &styled, params)
+            .await
+        {
+            Ok(summary) => {
+                let clean = self.clean_summary(&summary);
+                self.store_summary(&hash, &model, target, &clean).await;
+                clean
+            }
+            Err(e) => {
+                // Keep the opening of the group so the level still shrinks
+                tracing::warn!("Failed to merge summaries: {}", e);
+                truncated.store(true, Ordering::Relaxed);
+                text::truncate_text(&combined, MAX_INPUT_WORDS / 2)
+            }
+        }
+    }
+
+    /// Persist a finished chunk or merge summary; a failed write only costs a rerun later
+    async fn store_summary(&self, hash: &str, model: &str, target: Target<'_>, summary: &str) {
+        if summary.is_empty() {
+            return;
+        }
+        if let Err(e) = self
+            .db
+            .save_chunk_summary(hash, model, target.style, target.language.code, summary)
+            .await
+        {
+            tracing::warn!("Failed to store chunk summary {}: {}", hash, e);
+        }
+    }
+
+    /// Group consecutive texts so each group stays within `max_words`.
+    /// Every group holds at least two texts, so each level strictly shrinks.
+    fn group_by_words(&self, texts: Vec<String>, max_words: usize) -> Vec<Vec<String>> {
+        let mut groups: Vec<Vec<String>> = Vec::new();
+        let mut current: Vec<String> = Vec::new();
+        let mut current_words = 0;
+
+        for text in texts {
+            let words = text.split_whitespace().count();
+            if current.len() >= 2 && current_words + words > max_words {
+                groups.push(std::mem::take(&mut current));
+                current_words = 0;
+            }
+            current_words += words;
+            current.push(text);
+        }
+
+        if !current.is_empty() {
+            // A trailing single text joins the previous group instead of standing alone
+            match groups.last_mut() {
+                Some(last) if current.len() == 1 => last.append(&mut current),
+                _ => groups.push(current),
+            }
+        }
 
-        tracing::debug!("Applied '{}' style instruction to content", style);
+        groups
+    }
+
+    fn report(&self, progress: Option<&ProgressSender>, event: SummaryProgress) {
+        if let Some(sender) = progress {
+            // The receiver going away only means nobody is listening anymore
+            let _ = sender.send(event);
+        }
+    }
+
+    fn add_style_instruction(&self, text: &str, target: Target<'_>) -> String {
+        let instruction = target.language.instructions.for_style(target.style);
+
+        tracing::debug!(
+            "Applied '{}' style instruction in {} to content",
+            target.style,
+            target.language.name
+        );
         format!("{}\n\n{}", instruction, text)
     }
 
     /// Smart chunking by paragraphs to maintain context
-    fn smart_chunk_by_paragraphs(&self, text: &str, target_words: usize) -> Vec<String> {
+    fn smart_chunk_by_paragraphs(
+        &self,
+        text: &str,
+        target_words: usize,
+        target: Target<'_>,
+    ) -> Vec<String> {
         // For faster processing, create fewer, larger chunks
         // Split by double newlines (paragraphs) or sentences
         let paragraphs: Vec<&str> = text
@@ -240,7 +959,7 @@ impl SummarizerService {
                 }
 
                 // Split large paragraph by sentences
-                let sentences = self.split_into_sentences(para);
+                let sentences = self.split_into_sentences(para, target);
                 let mut sentence_chunk = Vec::new();
                 let mut sent_count = 0;
 
@@ -292,58 +1011,14 @@ impl SummarizerService {
         chunks
     }
 
-    /// Split text into sentences
-    fn split_into_sentences<'a>(&self, text: &'a str) -> Vec<&'a str> {
-        text.split(&['.', '!', '?'])
-            .filter(|s| !s.trim().is_empty())
+    /// Split text into sentences by the language's rules
+    fn split_into_sentences<'a>(&self, text: &'a str, target: Target<'_>) -> Vec<&'a str> {
+        text::sentence_spans(text, target.language.sentences)
+            .into_iter()
+            .map(|span| &text[span])
             .collect()
     }
 
-    /// Clean Project Gutenberg headers and footers
-    fn clean_project_gutenberg_text(&self, content: &str) -> String {
-        let lines: Vec<&str> = content.lines().collect();
-        let mut start_idx = 0;
-        let mut end_idx = lines.len();
-
-        // Find start of actual content
-        for (i, line) in lines.iter().enumerate() {
-            if line.contains("*** START OF") && line.contains("PROJECT GUTENBERG") {
-                start_idx = i + 1;
-                break;
-            }
-            if line.starts_with("CHAPTER") || line.starts_with("Chapter") {
-                start_idx = i;
-                break;
-            }
-        }
-
-        // Find end of actual content
-        for (i, line) in lines.iter().enumerate().rev() {
-            if line.contains("*** END OF") && line.contains("PROJECT GUTENBERG") {
-                end_idx = i;
-                break;
-            }
-        }
-
-        if start_idx < end_idx {
-            lines[start_idx..end_idx]
-                .iter()
-                .filter(|line| {
-                    let trimmed = line.trim();
-                    !trimmed.is_empty()
-                        && !trimmed.starts_with("Produced by")
-                        && !trimmed.starts_with("Updated:")
-                        && !trimmed.contains("gutenberg.org")
-                        && !trimmed.starts_with("[Illustration")
-                })
-                .map(|s| s.trim())
-                .collect::<Vec<_>>()
-                .join("\n")
-        } else {
-            content.to_string()
-        }
-    }
-
     /// Clean summary output
     fn clean_summary(&self, summary: &str) -> String {
         summary
@@ -355,47 +1030,176 @@ impl SummarizerService {
             .join("\n")
     }
 
-    /// Fallback summary for very short content
-    fn fallback_summary(&self, content: &str, style: &str) -> String {
-        let sentence_count = match style {
-            "detailed" => 20,
-            "academic" => 18,
-            "simple" => 12,
-            _ => 15, // concise
-        };
+    fn abstractive_summary(
+        &self,
+        summary: String,
+        target: Target<'_>,
+        budget: Budget,
+        chunk_params: Option<SummarizeParams>,
+        chunk_count: usize,
+        truncated: bool,
+    ) -> BookSummary {
+        let final_params = budget.params();
+        let mut parameters = json!({
+            "style": target.style,
+            "language": target.language.code,
+            "max_pages": budget.pages,
+            "words_per_page": self.words_per_page,
+            "target_words": budget.target_words,
+            "min_words": budget.min_words,
+            "max_words": budget.max_words,
+            "max_tokens": final_params.max_tokens,
+            "min_tokens": final_params.min_tokens,
+        });
+        if let Some(chunk_params) = chunk_params {
+            parameters["chunk_max_tokens"] = json!(chunk_params.max_tokens);
+            parameters["chunk_min_tokens"] = json!(chunk_params.min_tokens);
+            parameters["chunk_words"] = json!(MAX_INPUT_WORDS);
+        }
+
+        BookSummary {
+            summary,
+            method: SummaryMethod::Abstractive,
+            chapters: Vec::new(),
+            model: self
+                .backend
+                .text_model(&target.language.summarization_model),
+            parameters,
+            chunk_count,
+            truncated,
+        }
+    }
+
+    /// Offline TextRank summary used whenever the model cannot produce one
+    fn extractive_summary(
+        &self,
+        content: &str,
+        target: Target<'_>,
+        budget: Budget,
+        chunk_count: usize,
+        truncated: bool,
+    ) -> Result<BookSummary> {
+        let style = target.style;
+        let max_words = budget.target_words;
 
         tracing::info!(
-            "Using fallback summary with style '{}' and {} sentence limit",
+            "Using extractive summary with style '{}' and {} word budget",
             style,
-            sentence_count
+            max_words
         );
 
-        let sentences: Vec<&str> = content
-            .split(&['.', '!', '?'])
-            .filter(|s| {
-                let trimmed = s.trim();
-                trimmed.len() > 20
-                    && !trimmed.contains("Project Gutenberg")
-                    && !trimmed.starts_with("CHAPTER")
-            })
-            .take(sentence_count)
-            .collect();
+        let extract = extractive::summarize(
+            content,
+            max_words,
+            target.language.sentences,
+            target.language.stopwords,
+        )
+        .ok_or_else(|| {
+            AppError::ServiceError("No usable sentences for an extractive summary".to_string())
+        })?;
 
-        if sentences.is_empty() {
-            let default_message = match style {
-                "detailed" => "This comprehensive literary work presents an intricate narrative structure with well-developed characters, exploring complex themes through detailed storytelling techniques. The author employs various literary devices to create a rich reading experience that engages with profound questions and offers multiple layers of meaning for readers to discover and analyze.",
-                "academic" => "This literary work demonstrates significant artistic merit through its sophisticated narrative construction and thematic complexity. The text employs established literary conventions while contributing to broader cultural and intellectual discourse within its genre and historical context.",
-                "simple" => "This book tells an interesting story with good characters. It's easy to read and has important ideas that help readers learn and think about life.",
-                _ => "This literary work offers engaging storytelling with memorable characters and explores meaningful themes that resonate with readers.", // concise
-            };
-            default_message.to_string()
-        } else {
-            let joined = sentences.join(". ").trim().to_string() + ".";
-            match style {
-                "detailed" | "academic" => format!("This work presents: {}", joined),
-                "simple" => format!("This book is about: {}", joined),
-                _ => joined, // concise
-            }
-        }
+        Ok(BookSummary {
+            summary: extract.summary,
+            method: SummaryMethod::Extractive,
+            chapters: Vec::new(),
+            model: extractive::MODEL_ID.to_string(),
+            parameters: json!({
+                "style": style,
+                "language": target.language.code,
+                "max_pages": budget.pages,
+                "words_per_page": self.words_per_page,
+                "max_words": max_words,
+                "max_sentences": extractive::MAX_GRAPH_SENTENCES,
+            }),
+            chunk_count,
+            truncated: truncated || extract.sampled,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budget_follows_pages_and_style() {
+        let detailed = Budget::new(2, 300, "detailed");
+        assert_eq!(detailed.max_words, 600);
+        assert_eq!(detailed.target_words, 600);
+        assert_eq!(detailed.min_words, 420);
+
+        let concise = Budget::new(2, 300, "concise");
+        assert_eq!(concise.max_words, 600);
+        assert_eq!(concise.target_words, 330);
+        assert_eq!(concise.min_words, 231);
+    }
+
+    #[test]
+    fn share_divides_target_and_limit() {
+        let budget = Budget::new(2, 300, "detailed").share(3);
+        assert_eq!(budget.target_words, 200);
+        assert_eq!(budget.max_words, 200);
+        assert_eq!(budget.min_words, 140);
+        assert_eq!(budget.pages, 2);
+
+        let whole = Budget::new(2, 300, "detailed");
+        assert_eq!(whole.share(0).target_words, whole.target_words);
+        assert_eq!(whole.share(1).max_words, whole.max_words);
+    }
+
+    #[test]
+    fn chunk_params_stay_within_chunk_bounds() {
+        let budget = Budget::new(1, 300, "detailed");
+
+        let few = budget.chunk_params(1);
+        assert_eq!(few.max_tokens, tokens(MAX_CHUNK_WORDS));
+        assert_eq!(few.min_tokens, tokens(MAX_CHUNK_WORDS / 4));
+
+        let many = budget.chunk_params(100);
+        assert_eq!(many.max_tokens, tokens(MIN_CHUNK_WORDS));
+        assert_eq!(many.min_tokens, tokens(MIN_CHUNK_WORDS / 4));
+
+        let between = budget.chunk_params(4);
+        assert_eq!(between.max_tokens, tokens(150));
+    }
+
+    #[test]
+    fn material_words_is_twice_the_budget_or_one_input() {
+        assert_eq!(
+            Budget::new(1, 100, "detailed").material_words(),
+            MAX_INPUT_WORDS
+        );
+        assert_eq!(Budget::new(5, 300, "detailed").material_words(), 3000);
+    }
+
+    #[test]
+    fn params_stay_within_the_output_limit() {
+        let short = Budget::new(1, 300, "concise");
+        assert_eq!(short.sections(), 1);
+        let params = short.params();
+        assert!(params.max_tokens < MAX_OUTPUT_TOKENS);
+        assert!(params.min_tokens < params.max_tokens);
+
+        let long = Budget::new(10, 300, "detailed");
+        let params = long.params();
+        assert_eq!(params.max_tokens, MAX_OUTPUT_TOKENS);
+        assert!(params.min_tokens < params.max_tokens);
+
+        let section = long.share(long.sections()).params();
+        assert!(section.max_tokens <= MAX_OUTPUT_TOKENS);
+        assert_eq!(long.share(long.sections()).sections(), 1);
+    }
+
+    #[test]
+    fn for_source_keeps_summaries_short_of_the_source() {
+        let budget = Budget::new(2, 300, "detailed").for_source(400);
+        assert_eq!(budget.target_words, 200);
+        assert_eq!(budget.max_words, 400);
+
+        let unchanged = Budget::new(1, 300, "concise");
+        assert_eq!(
+            unchanged.for_source(10_000).target_words,
+            unchanged.target_words
+        );
     }
 }
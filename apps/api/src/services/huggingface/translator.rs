@@ -0,0 +1,117 @@
+use futures::stream::{self, StreamExt};
+use std::sync::Arc;
+
+use crate::{
+    config::{LanguageProfile, Languages},
+    services::inference::InferenceBackend,
+    utils::{
+        errors::{AppError, Result},
+        text,
+    },
+};
+
+// Translation models truncate long inputs, so text is sent in sentence-aligned pieces
+const MAX_CHUNK_CHARS: usize = 400;
+const CHUNK_CONCURRENCY: usize = 3;
+
+/// Translated text and the model that produced it
+pub struct Translation {
+    pub text: String,
+    pub model: String,
+}
+
+pub struct TranslatorService {
+    backend: Arc<dyn InferenceBackend>,
+    model: String,
+    languages: Arc<Languages>,
+}
+
+impl TranslatorService {
+    pub fn new(
+        backend: Arc<dyn InferenceBackend>,
+        model: String,
+        languages: Arc<Languages>,
+    ) -> Self {
+        Self {
+            backend,
+            model,
+            languages,
+        }
+    }
+
+    /// Translate `text` between two supported languages, keeping its line breaks.
+    ///
+    /// Each line is split into sentence-aligned pieces that are translated concurrently;
+    /// if any piece fails the whole translation fails rather than mixing languages.
+    pub async fn translate(&self, text: &str, from: &str, to: &str) -> Result<Translation> {
+        let source = self.languages.resolve(from)?;
+        let target = self.languages.resolve(to)?;
+
+        let lines: Vec<Vec<String>> = text
+            .lines()
+            .map(|line| text::split_sentence_chunks(line.trim(), MAX_CHUNK_CHARS))
+            .filter(|pieces| !pieces.is_empty())
+            .collect();
+
+        let total: usize = lines.iter().map(Vec::len).sum();
+        if total == 0 {
+            return Err(AppError::InvalidInput(
+                "No text available for translation".to_string(),
+            ));
+        }
+
+        tracing::info!(
+            "Translating {} characters from {} to {} in {} pieces with {}",
+            text.len(),
+            source.code,
+            target.code,
+            total,
+            self.backend.text_model(&self.model)
+        );
+
+        let tasks: Vec<_> = lines
+            .iter()
+            .flatten()
+            .map(|piece| self.translate_piece(piece, source, target))
+            .collect();
+        let mut translated = stream::iter(tasks)
+            .buffered(CHUNK_CONCURRENCY)
+            .collect::<Vec<Result<String>>>()
+            .await
+            .into_iter();
+
+        let mut output = Vec::with_capacity(lines.len());
+        for line in &lines {
+            let pieces = translated
+                .by_ref()
+                .take(line.len())
+                .collect::<Result<Vec<String>>>()?;
+            output.push(pieces.join(" "));
+        }
+
+        Ok(Translation {
+            text: output.join("\n"),
+            model: self.backend.text_model(&self.model),
+        })
+    }
+
+    async fn translate_piece(
+        &self,
+        piece: &str,
+        source: &LanguageProfile,
+        target: &LanguageProfile,
+    ) -> Result<String> {
+        self.backend
+            .translate(&self.model, piece, source, target)
+            .await
+            .map_err(|e| {
+                tracing::warn!(
+                    "Failed to translate piece from {} to {}: {}",
+                    source.code,
+                    target.code,
+                    e
+                );
+                e
+            })
+    }
+}
@@ -1,129 +1,242 @@
+use futures::stream::{self, StreamExt};
+use std::sync::Arc;
+
 use crate::{
-    services::huggingface::client::HuggingFaceClient,
-    utils::{errors::Result, text},
+    config::{LanguageProfile, Languages},
+    models::{AudioTimings, Voice},
+    services::inference::InferenceBackend,
+    utils::{
+        audio,
+        errors::{AppError, Result},
+        text,
+        timing::{self, PlannedChunk, SpokenChunk},
+        wav,
+    },
 };
 
-const TTS_MODEL: &str = "espnet/kan-bayashi_ljspeech_vits";
-const BACKUP_TTS_MODEL: &str = "facebook/fastspeech2-en-ljspeech";
+// TTS models degrade or reject input much past this length
+const MAX_CHUNK_CHARS: usize = 600;
+const CHUNK_CONCURRENCY: usize = 3;
+const CHUNK_PAUSE_MS: u32 = 300;
+
+/// Encoded audio from `TTSService::generate_audio`
+pub struct SynthesizedAudio {
+    pub data: Vec<u8>,
+    /// Every TTS model failed and `data` is the synthetic placeholder tone
+    pub is_placeholder: bool,
+    /// When each sentence and word of the input text is spoken
+    pub timings: AudioTimings,
+}
 
 pub struct TTSService {
-    client: HuggingFaceClient,
+    backend: Arc<dyn InferenceBackend>,
+    languages: Arc<Languages>,
 }
 
 impl TTSService {
-    pub fn new(client: HuggingFaceClient) -> Self {
-        Self { client }
+    pub fn new(backend: Arc<dyn InferenceBackend>, languages: Arc<Languages>) -> Self {
+        Self { backend, languages }
+    }
+
+    /// Voices offered by the backend, optionally limited to one language
+    pub fn voices(&self, language: Option<&str>) -> Vec<Voice> {
+        self.backend
+            .voices(&self.languages)
+            .into_iter()
+            .filter(|voice| language.is_none_or(|l| voice.language == l))
+            .collect()
     }
 
-    pub async fn generate_audio(&self, text: &str, language: &str) -> Result<Vec<u8>> {
-        let truncated = text::truncate_text(text, 500);
-        let cleaned_text = self.clean_text_for_tts(&truncated);
+    /// Look up a catalog voice; `None` or `default` picks the language's default voice
+    pub fn resolve_voice(&self, language: &str, voice_type: Option<&str>) -> Result<Voice> {
+        let voices = self.voices(Some(language));
+        let requested = voice_type.filter(|v| !v.is_empty() && *v != "default");
+
+        let voice = match requested {
+            Some(id) => voices.iter().find(|voice| voice.id == id),
+            None => voices
+                .iter()
+                .find(|voice| voice.is_default)
+                .or(voices.first()),
+        };
+
+        voice.cloned().ok_or_else(|| {
+            let available = voices
+                .iter()
+                .map(|voice| voice.id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            AppError::InvalidInput(format!(
+                "Unknown voice '{}' for language {}. Available: {}",
+                requested.unwrap_or("default"),
+                language,
+                if available.is_empty() {
+                    "none"
+                } else {
+                    &available
+                }
+            ))
+        })
+    }
 
-        if cleaned_text.trim().is_empty() {
-            return Err(crate::utils::errors::AppError::InvalidInput(
+    /// Synthesize the whole text in `voice`, reading it in sentence-aligned chunks.
+    ///
+    /// Chunks are synthesized concurrently and joined into one WAV with a short pause
+    /// between them. Only the default voice falls back to the language's other models,
+    /// so an explicitly chosen voice is never swapped for a different one. Sentences are
+    /// split and normalized by the language's rules. If any chunk cannot be
+    /// synthesized, a placeholder tone covering the whole text is returned instead,
+    /// flagged with `is_placeholder`, and its timings are estimated from text length.
+    /// Chunks that were synthesized but cannot be joined (e.g. not WAV) and rejected
+    /// credentials are errors, since retrying later would not fix them.
+    pub async fn generate_audio(
+        &self,
+        text: &str,
+        language: &str,
+        voice: &Voice,
+    ) -> Result<SynthesizedAudio> {
+        let profile = self.languages.resolve(language)?;
+
+        // Chunks are planned from the original sentences so timings map back onto `text`
+        let sentences = text::sentence_spans(text, profile.sentences);
+        let chunks = timing::plan_chunks(text, &sentences, MAX_CHUNK_CHARS, |sentence| {
+            self.clean_text_for_tts(sentence, profile)
+        });
+
+        if chunks.is_empty() {
+            return Err(AppError::InvalidInput(
                 "No valid text for audio generation".to_string(),
             ));
         }
 
+        let cleaned_text = chunks
+            .iter()
+            .map(|chunk| chunk.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
         tracing::info!(
-            "Generating audio for language: {} with {} characters",
+            "Generating audio for language: {} in voice {} with {} characters in {} chunks",
             language,
-            cleaned_text.len()
+            voice.id,
+            cleaned_text.len(),
+            chunks.len()
         );
 
-        // Try language-specific model first
-        let primary_model = self.get_tts_model(language);
+        let mut attempts = vec![(voice.model.as_str(), voice.speaker_id.as_deref())];
+        if voice.is_default {
+            for model in &profile.tts_models {
+                if !attempts.iter().any(|(m, _)| *m == model.as_str()) {
+                    attempts.push((model.as_str(), None));
+                }
+            }
+        }
 
-        // First attempt with primary model
-        match self
-            .generate_with_retry(primary_model, &cleaned_text, 3)
-            .await
-        {
-            Ok(audio_data) => {
-                tracing::info!("Successfully generated audio with {} model", primary_model);
-                return Ok(audio_data);
+        let tasks: Vec<_> = chunks
+            .iter()
+            .enumerate()
+            .map(|(index, chunk)| self.synthesize_chunk(&attempts, index, &chunk.text))
+            .collect();
+        let results: Vec<Result<Vec<u8>>> = stream::iter(tasks)
+            .buffered(CHUNK_CONCURRENCY)
+            .collect()
+            .await;
+
+        let e = match results.iter().position(|r| r.is_err()) {
+            None => {
+                let parts: Vec<Vec<u8>> = results.into_iter().flatten().collect();
+                let (audio_data, spoken) = join_parts(parts, &chunks).map_err(|e| {
+                    tracing::error!("Failed to join {} synthesized chunks: {}", chunks.len(), e);
+                    AppError::ExternalApi(format!("Failed to join synthesized audio: {}", e))
+                })?;
+                return Ok(SynthesizedAudio {
+                    data: audio_data,
+                    is_placeholder: false,
+                    timings: timing::align(text, &sentences, &spoken),
+                });
             }
-            Err(e) => {
+            Some(index) => {
+                let e = results
+                    .into_iter()
+                    .nth(index)
+                    .and_then(|r| r.err())
+                    .expect("position points at an error");
                 tracing::warn!(
-                    "Failed to generate audio with {}: {}. Trying fallbacks...",
-                    primary_model,
+                    "TTS failed for chunk {} of {}: {}",
+                    index + 1,
+                    chunks.len(),
                     e
                 );
-
-                // Fallback 1: Try main TTS model if not already used
-                if primary_model != TTS_MODEL {
-                    match self.generate_with_retry(TTS_MODEL, &cleaned_text, 3).await {
-                        Ok(audio_data) => {
-                            tracing::info!("Successfully generated audio with main TTS model");
-                            return Ok(audio_data);
-                        }
-                        Err(fallback_e) => {
-                            tracing::warn!("Main TTS model also failed: {}", fallback_e);
-                        }
-                    }
+                if is_auth_error(&e) {
+                    return Err(e);
                 }
+                e
+            }
+        };
 
-                // Fallback 2: Try backup model
-                if primary_model != BACKUP_TTS_MODEL && TTS_MODEL != BACKUP_TTS_MODEL {
-                    match self
-                        .generate_with_retry(BACKUP_TTS_MODEL, &cleaned_text, 3)
-                        .await
-                    {
-                        Ok(audio_data) => {
-                            tracing::info!("Successfully generated audio with backup model");
-                            return Ok(audio_data);
-                        }
-                        Err(backup_e) => {
-                            tracing::warn!("Backup TTS model failed: {}", backup_e);
-                        }
-                    }
-                }
+        // Final fallback: Generate a realistic audio placeholder
+        tracing::warn!("All HuggingFace TTS strategies failed, using enhanced fallback audio");
+        match self.generate_fallback_audio(&cleaned_text, language) {
+            Ok(audio_data) => {
+                tracing::info!("Successfully generated fallback audio with realistic duration");
+                let duration_ms = audio::probe(&audio_data)?.duration_ms;
+                Ok(SynthesizedAudio {
+                    data: audio_data,
+                    is_placeholder: true,
+                    timings: timing::estimate(text, profile.sentences, duration_ms),
+                })
+            }
+            Err(fallback_e) => {
+                tracing::error!("Even fallback audio generation failed: {}", fallback_e);
+                Err(AppError::ExternalApi(format!(
+                    "TTS generation failed for language {} after trying all strategies. Original error: {}. HuggingFace TTS models may not be available via the Inference API. Consider using a dedicated TTS service.",
+                    language, e
+                )))
+            }
+        }
+    }
 
-                // Fallback 3: Try with shortened text
-                let short_text =
-                    self.clean_text_for_tts(&cleaned_text[..cleaned_text.len().min(200)]);
-                if !short_text.is_empty() && short_text != cleaned_text {
-                    tracing::info!(
-                        "Attempting TTS with shortened text ({} chars)",
-                        short_text.len()
+    /// Synthesize one chunk, trying each (model, speaker) pair in turn
+    async fn synthesize_chunk(
+        &self,
+        attempts: &[(&str, Option<&str>)],
+        index: usize,
+        chunk: &str,
+    ) -> Result<Vec<u8>> {
+        let mut last_error = None;
+        for (model, speaker) in attempts.iter().copied() {
+            match self.generate_with_retry(model, speaker, chunk, 3).await {
+                Ok(audio_data) => {
+                    tracing::debug!(
+                        "Synthesized chunk {} ({} chars) with {}",
+                        index + 1,
+                        chunk.len(),
+                        model
                     );
-                    match self.generate_with_retry(TTS_MODEL, &short_text, 2).await {
-                        Ok(audio_data) => {
-                            tracing::info!("Successfully generated audio with shortened text");
-                            return Ok(audio_data);
-                        }
-                        Err(short_e) => {
-                            tracing::warn!("Shortened text TTS failed: {}", short_e);
-                        }
-                    }
+                    return Ok(audio_data);
                 }
-
-                // Final fallback: Generate a realistic audio placeholder
-                tracing::warn!(
-                    "All HuggingFace TTS strategies failed, using enhanced fallback audio"
-                );
-                match self.generate_fallback_audio(&cleaned_text, language) {
-                    Ok(audio_data) => {
-                        tracing::info!(
-                            "Successfully generated fallback audio with realistic duration"
-                        );
-                        Ok(audio_data)
-                    }
-                    Err(fallback_e) => {
-                        tracing::error!("Even fallback audio generation failed: {}", fallback_e);
-                        Err(crate::utils::errors::AppError::ExternalApi(format!(
-                            "TTS generation failed for language {} after trying all strategies. Original error: {}. HuggingFace TTS models may not be available via the Inference API. Consider using a dedicated TTS service.",
-                            language, e
-                        )))
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to synthesize chunk {} with {}: {}",
+                        index + 1,
+                        model,
+                        e
+                    );
+                    // Every model shares the same credentials
+                    if is_auth_error(&e) {
+                        return Err(e);
                     }
+                    last_error = Some(e);
                 }
             }
         }
+
+        Err(last_error.unwrap_or_else(|| AppError::ExternalApi("Unknown TTS error".to_string())))
     }
 
     async fn generate_with_retry(
         &self,
         model: &str,
+        speaker: Option<&str>,
         text: &str,
         max_retries: u32,
     ) -> Result<Vec<u8>> {
@@ -137,12 +250,11 @@ impl TTSService {
                 model
             );
 
-            match self.client.tts(model, text).await {
+            match self.backend.synthesize(model, speaker, text).await {
                 Ok(data) => {
                     if data.is_empty() {
-                        let error = crate::utils::errors::AppError::ExternalApi(
-                            "TTS returned empty audio data".to_string(),
-                        );
+                        let error =
+                            AppError::ExternalApi("TTS returned empty audio data".to_string());
                         last_error = Some(error);
                         tracing::warn!("TTS returned empty data on attempt {}", attempt);
                         continue;
@@ -150,7 +262,7 @@ impl TTSService {
 
                     // Validate it's actual audio, not just a few bytes
                     if data.len() < 1000 {
-                        let error = crate::utils::errors::AppError::ExternalApi(format!(
+                        let error = AppError::ExternalApi(format!(
                             "TTS returned insufficient audio data: {} bytes",
                             data.len()
                         ));
@@ -168,6 +280,9 @@ impl TTSService {
                 }
                 Err(e) => {
                     tracing::warn!("TTS attempt {}/{} failed: {}", attempt, max_retries, e);
+                    if is_auth_error(&e) {
+                        return Err(e);
+                    }
                     last_error = Some(e);
 
                     if attempt < max_retries {
@@ -180,35 +295,25 @@ impl TTSService {
             }
         }
 
-        Err(last_error.unwrap_or_else(|| {
-            crate::utils::errors::AppError::ExternalApi("Unknown TTS error".to_string())
-        }))
+        Err(last_error.unwrap_or_else(|| AppError::ExternalApi("Unknown TTS error".to_string())))
     }
 
-    fn clean_text_for_tts(&self, text: &str) -> String {
-        let cleaned = text
-            // Remove markdown and formatting
+    fn clean_text_for_tts(&self, text: &str, profile: &LanguageProfile) -> String {
+        // Remove markdown and formatting
+        let mut cleaned = text
             .replace("**", "")
             .replace("*", "")
             .replace("_", "")
             .replace("#", "")
             .replace("`", "")
-            .replace("~", "")
-            // Replace problematic characters
-            .replace("&", "and")
-            .replace("@", "at")
-            .replace("%", "percent")
-            .replace("$", "dollars")
-            .replace("€", "euros")
-            .replace("£", "pounds")
-            // Replace common abbreviations that might cause issues
-            .replace("e.g.", "for example")
-            .replace("i.e.", "that is")
-            .replace("etc.", "and so on")
-            .replace("vs.", "versus")
-            .replace("Mr.", "Mister")
-            .replace("Mrs.", "Missus")
-            .replace("Dr.", "Doctor")
+            .replace("~", "");
+
+        // Spell out symbols and abbreviations the way the language reads them
+        for (from, to) in profile.tts_normalization {
+            cleaned = cleaned.replace(from, to);
+        }
+
+        cleaned
             // Remove URLs and email patterns
             .split_whitespace()
             .filter(|word| !word.contains("http") && !word.contains("@"))
@@ -216,36 +321,12 @@ impl TTSService {
             .join(" ")
             // Remove special characters that might cause TTS issues
             .chars()
-            .filter(|c| c.is_alphanumeric() || " .,!?;:'-".contains(*c))
+            .filter(|c| text::is_word_char(*c) || " .,!?;:'-".contains(*c))
             .collect::<String>()
             // Clean up extra whitespace and punctuation
             .split_whitespace()
             .collect::<Vec<_>>()
             .join(" ")
-            // Limit to reasonable length for TTS
-            .chars()
-            .take(1000)
-            .collect::<String>();
-
-        // Ensure we don't end mid-sentence
-        if cleaned.len() >= 1000 {
-            if let Some(last_sentence_end) = cleaned.rfind(&['.', '!', '?'][..]) {
-                if last_sentence_end > 500 {
-                    // Only truncate if we have at least 500 chars
-                    return cleaned[..=last_sentence_end].to_string();
-                }
-            }
-        }
-
-        cleaned
-    }
-
-    fn get_tts_model(&self, language: &str) -> &str {
-        match language {
-            "de" => "facebook/mms-tts-deu",
-            "ta" => "facebook/mms-tts-tam",
-            _ => TTS_MODEL, // English (en) and fallback
-        }
     }
 
     /// Generate a fallback audio file when TTS services fail
@@ -254,9 +335,8 @@ impl TTSService {
         // Calculate realistic duration based on average reading speed
         let word_count = text.split_whitespace().count();
         let words_per_minute = 150.0; // Average TTS speed
-        let duration_seconds = ((word_count as f32 / words_per_minute * 60.0) + 2.0)
-            .min(45.0)
-            .max(3.0);
+        let duration_seconds =
+            ((word_count as f32 / words_per_minute * 60.0) + 2.0).clamp(3.0, 45.0);
 
         let sample_rate = 22050u32;
         let channels = 1u16;
@@ -329,3 +409,45 @@ impl TTSService {
         Ok(wav_data)
     }
 }
+
+/// Whether the backend rejected our credentials (HTTP 401 or 403)
+fn is_auth_error(error: &AppError) -> bool {
+    matches!(error, AppError::ExternalApi(msg) if msg.contains("error 401") || msg.contains("error 403"))
+}
+
+/// Join synthesized chunks into one WAV and record where each chunk sits in it
+fn join_parts<'a>(
+    mut parts: Vec<Vec<u8>>,
+    chunks: &'a [PlannedChunk],
+) -> Result<(Vec<u8>, Vec<SpokenChunk<'a>>)> {
+    let mut spoken = Vec::with_capacity(parts.len());
+    let mut cursor = 0;
+    for (part, chunk) in parts.iter().zip(chunks) {
+        let info = audio::probe(part)?;
+        if parts.len() > 1 && info.content_type != "audio/wav" {
+            return Err(AppError::ServiceError(format!(
+                "TTS returned {} audio, but only WAV chunks can be joined",
+                info.content_type
+            )));
+        }
+        let duration_ms = info.duration_ms;
+        spoken.push(SpokenChunk {
+            units: &chunk.units,
+            start_ms: cursor,
+            end_ms: cursor + duration_ms,
+        });
+        cursor += duration_ms + CHUNK_PAUSE_MS as u64;
+    }
+
+    if parts.len() == 1 {
+        return Ok((parts.remove(0), spoken));
+    }
+
+    let audio_data = wav::concatenate(&parts, CHUNK_PAUSE_MS)?;
+    tracing::info!(
+        "Joined {} audio chunks into {} bytes",
+        parts.len(),
+        audio_data.len()
+    );
+    Ok((audio_data, spoken))
+}
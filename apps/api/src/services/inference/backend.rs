@@ -0,0 +1,50 @@
+use async_trait::async_trait;
+
+use crate::{
+    config::{LanguageProfile, Languages},
+    models::Voice,
+    utils::errors::Result,
+};
+
+/// Length bounds for a summarization call, in model tokens
+#[derive(Debug, Clone, Copy)]
+pub struct SummarizeParams {
+    pub max_tokens: usize,
+    pub min_tokens: usize,
+}
+
+/// Model server used for summarization, translation, free-form generation and speech synthesis.
+///
+/// `model` is a hint naming the model the caller would like to use. Backends that
+/// serve a fixed, configured set of models may ignore it.
+#[async_trait]
+pub trait InferenceBackend: Send + Sync {
+    /// Short name used in logs and health output
+    fn name(&self) -> &str;
+
+    /// Model that actually serves text requests for the requested `model` hint
+    fn text_model(&self, model: &str) -> String {
+        model.to_string()
+    }
+
+    async fn summarize(&self, model: &str, text: &str, params: SummarizeParams) -> Result<String>;
+
+    async fn generate(&self, model: &str, prompt: &str) -> Result<String>;
+
+    /// Translate `text` from the `source` language into the `target` language
+    async fn translate(
+        &self,
+        model: &str,
+        text: &str,
+        source: &LanguageProfile,
+        target: &LanguageProfile,
+    ) -> Result<String>;
+
+    /// Voices this backend can synthesize in the enabled `languages`
+    fn voices(&self, languages: &Languages) -> Vec<Voice>;
+
+    /// Synthesize speech, returning the encoded audio bytes (WAV).
+    ///
+    /// `speaker` selects a speaker within a multi-speaker model or server.
+    async fn synthesize(&self, model: &str, speaker: Option<&str>, text: &str) -> Result<Vec<u8>>;
+}
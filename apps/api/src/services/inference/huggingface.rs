@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+
+use crate::{
+    config::{LanguageProfile, Languages},
+    models::Voice,
+    services::{
+        huggingface::HuggingFaceClient,
+        inference::{InferenceBackend, SummarizeParams},
+    },
+    utils::errors::Result,
+};
+
+// (id, language, model, gender, description)
+const VOICES: [(&str, &str, &str, &str, &str); 5] = [
+    (
+        "ljspeech-vits",
+        "en",
+        "espnet/kan-bayashi_ljspeech_vits",
+        "female",
+        "LJSpeech narrator, VITS model",
+    ),
+    (
+        "ljspeech-fastspeech2",
+        "en",
+        "facebook/fastspeech2-en-ljspeech",
+        "female",
+        "LJSpeech narrator, FastSpeech 2 model",
+    ),
+    (
+        "mms-eng",
+        "en",
+        "facebook/mms-tts-eng",
+        "male",
+        "Massively Multilingual Speech English voice",
+    ),
+    (
+        "mms-deu",
+        "de",
+        "facebook/mms-tts-deu",
+        "male",
+        "Massively Multilingual Speech German voice",
+    ),
+    (
+        "mms-tam",
+        "ta",
+        "facebook/mms-tts-tam",
+        "male",
+        "Massively Multilingual Speech Tamil voice",
+    ),
+];
+
+/// Catalog voices for `language`, none of them marked as its default
+fn catalog_voices(language: &'static str) -> impl Iterator<Item = Voice> {
+    VOICES
+        .iter()
+        .filter(move |(_, voice_language, ..)| *voice_language == language)
+        .map(|(id, language, model, gender, description)| Voice {
+            id: id.to_string(),
+            language: language.to_string(),
+            model: model.to_string(),
+            speaker_id: None,
+            gender: gender.to_string(),
+            description: description.to_string(),
+            is_default: false,
+        })
+}
+
+/// Inference backed by the HuggingFace Inference API
+pub struct HuggingFaceBackend {
+    client: HuggingFaceClient,
+}
+
+impl HuggingFaceBackend {
+    pub fn new(client: HuggingFaceClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl InferenceBackend for HuggingFaceBackend {
+    fn name(&self) -> &str {
+        "huggingface"
+    }
+
+    async fn summarize(&self, model: &str, text: &str, params: SummarizeParams) -> Result<String> {
+        self.client
+            .summarize_bart(model, text, params.max_tokens, params.min_tokens)
+            .await
+    }
+
+    async fn generate(&self, model: &str, prompt: &str) -> Result<String> {
+        self.client.text_generation(model, prompt).await
+    }
+
+    async fn translate(
+        &self,
+        model: &str,
+        text: &str,
+        source: &LanguageProfile,
+        target: &LanguageProfile,
+    ) -> Result<String> {
+        self.client
+            .translate(
+                model,
+                text,
+                source.translation_code,
+                target.translation_code,
+            )
+            .await
+    }
+
+    /// Each language's default voice is its first configured TTS model, described from
+    /// the catalog when the catalog knows it; other catalog voices follow it
+    fn voices(&self, languages: &Languages) -> Vec<Voice> {
+        languages
+            .iter()
+            .flat_map(|profile| {
+                let default_model = profile.tts_models.first();
+                let default = default_model.map(|model| Voice {
+                    is_default: true,
+                    ..catalog_voices(profile.code)
+                        .find(|voice| voice.model == *model)
+                        .unwrap_or_else(|| Voice {
+                            id: model.rsplit('/').next().unwrap_or(model).to_string(),
+                            language: profile.code.to_string(),
+                            model: model.clone(),
+                            speaker_id: None,
+                            gender: "unspecified".to_string(),
+                            description: format!("Configured {} voice", profile.name),
+                            is_default: true,
+                        })
+                });
+
+                default.into_iter().chain(
+                    catalog_voices(profile.code)
+                        .filter(move |voice| Some(&voice.model) != default_model),
+                )
+            })
+            .collect()
+    }
+
+    // The catalog models are all single-speaker
+    async fn synthesize(&self, model: &str, _speaker: Option<&str>, text: &str) -> Result<Vec<u8>> {
+        self.client.tts(model, text).await
+    }
+}
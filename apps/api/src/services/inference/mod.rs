@@ -0,0 +1,35 @@
+mod backend;
+mod huggingface;
+mod openai;
+
+use std::sync::Arc;
+
+pub use backend::{InferenceBackend, SummarizeParams};
+pub use huggingface::HuggingFaceBackend;
+pub use openai::OpenAiBackend;
+
+use crate::{config::Settings, services::huggingface::HuggingFaceClient};
+
+/// Build the inference backend named `name` (`huggingface` or `openai`), such as
+/// `Settings::inference_backend` or `Settings::translation_backend`
+pub fn create_backend(
+    name: &str,
+    config: &Settings,
+    http_client: reqwest::Client,
+) -> Arc<dyn InferenceBackend> {
+    match name {
+        "openai" => Arc::new(OpenAiBackend::new(
+            http_client,
+            config.openai_api_base_url.clone(),
+            config.openai_api_key.clone(),
+            config.openai_chat_model.clone(),
+            config.openai_tts_model.clone(),
+            config.openai_tts_voice.clone(),
+        )),
+        _ => Arc::new(HuggingFaceBackend::new(HuggingFaceClient::new(
+            http_client,
+            config.hf_api_base_url.clone(),
+            config.hf_token.clone(),
+        ))),
+    }
+}
@@ -0,0 +1,293 @@
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+use crate::{
+    config::{LanguageProfile, Languages},
+    models::Voice,
+    services::inference::{InferenceBackend, SummarizeParams},
+    utils::errors::{AppError, Result},
+};
+
+// (voice, gender, description)
+const VOICES: [(&str, &str, &str); 6] = [
+    ("alloy", "neutral", "Balanced, even-toned voice"),
+    ("echo", "male", "Clear, resonant voice"),
+    ("fable", "male", "Expressive storytelling voice"),
+    ("onyx", "male", "Deep, authoritative voice"),
+    ("nova", "female", "Bright, energetic voice"),
+    ("shimmer", "female", "Soft, warm voice"),
+];
+
+const SUMMARY_SYSTEM_PROMPT: &str = "You summarize books. Follow any INSTRUCTION at the top of the user message and reply with the summary text only.";
+
+const TRANSLATION_SYSTEM_PROMPT: &str = "You translate book summaries. Keep the meaning, names and paragraph breaks, and reply with the translation only.";
+
+// Translations run longer than their source in tokens for many scripts
+const MAX_TRANSLATION_TOKENS: usize = 4000;
+
+/// Inference against an OpenAI-compatible server (`/v1/chat/completions`, `/v1/audio/speech`).
+///
+/// The server decides which models are available, so the HuggingFace model names the
+/// services pass in are ignored in favour of the configured chat and speech models.
+pub struct OpenAiBackend {
+    client: Client,
+    api_base: String,
+    api_key: Option<String>,
+    chat_model: String,
+    tts_model: String,
+    tts_voice: String,
+}
+
+impl OpenAiBackend {
+    pub fn new(
+        client: Client,
+        api_base: String,
+        api_key: Option<String>,
+        chat_model: String,
+        tts_model: String,
+        tts_voice: String,
+    ) -> Self {
+        tracing::info!(
+            "Initializing OpenAI-compatible backend with base URL: {}, chat model: {}",
+            api_base,
+            chat_model
+        );
+        Self {
+            client,
+            api_base: api_base.trim_end_matches('/').to_string(),
+            api_key,
+            chat_model,
+            tts_model,
+            tts_voice,
+        }
+    }
+
+    fn post(&self, path: &str) -> RequestBuilder {
+        let request = self.client.post(format!("{}{}", self.api_base, path));
+        match &self.api_key {
+            Some(key) if !key.is_empty() => request.bearer_auth(key),
+            _ => request,
+        }
+    }
+
+    async fn chat_completion(
+        &self,
+        system: Option<&str>,
+        user: &str,
+        max_tokens: usize,
+    ) -> Result<String> {
+        let mut messages = Vec::new();
+        if let Some(system) = system {
+            messages.push(json!({ "role": "system", "content": system }));
+        }
+        messages.push(json!({ "role": "user", "content": user }));
+
+        let payload = json!({
+            "model": self.chat_model,
+            "messages": messages,
+            "max_tokens": max_tokens,
+            "temperature": 0.3,
+        });
+
+        let max_retries = 3;
+        let mut last_error = None;
+
+        for attempt in 1..=max_retries {
+            tracing::debug!(
+                "Chat completion attempt {} for model: {}",
+                attempt,
+                self.chat_model
+            );
+
+            match self
+                .post("/v1/chat/completions")
+                .json(&payload)
+                .timeout(Duration::from_secs(90))
+                .send()
+                .await
+            {
+                Ok(response) => {
+                    let status = response.status();
+
+                    if status.is_success() {
+                        match response.json::<ChatCompletionResponse>().await {
+                            Ok(result) => {
+                                let text = result
+                                    .choices
+                                    .into_iter()
+                                    .next()
+                                    .and_then(|c| c.message.content)
+                                    .unwrap_or_default();
+
+                                if !text.trim().is_empty() {
+                                    return Ok(text.trim().to_string());
+                                }
+
+                                last_error = Some(AppError::ExternalApi(
+                                    "Chat completion returned empty content".to_string(),
+                                ));
+                            }
+                            Err(e) => {
+                                last_error = Some(AppError::ExternalApi(format!(
+                                    "Failed to parse chat completion response: {}",
+                                    e
+                                )));
+                            }
+                        }
+                    } else {
+                        let error_text = response.text().await.unwrap_or_default();
+                        last_error = Some(AppError::ExternalApi(format!(
+                            "Chat completion error {}: {}",
+                            status, error_text
+                        )));
+
+                        // Don't retry on authentication errors
+                        if status == 401 || status == 403 {
+                            return Err(last_error.unwrap());
+                        }
+                    }
+                }
+                Err(e) => {
+                    last_error = Some(AppError::ExternalApi(format!(
+                        "Failed to connect to chat completion API: {}",
+                        e
+                    )));
+                }
+            }
+
+            if attempt < max_retries {
+                let delay = Duration::from_millis(1000 * attempt as u64);
+                tracing::warn!(
+                    "Chat completion attempt {} failed, retrying in {:?}",
+                    attempt,
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| AppError::ExternalApi("Unknown chat completion error".to_string())))
+    }
+}
+
+#[async_trait]
+impl InferenceBackend for OpenAiBackend {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    fn text_model(&self, _model: &str) -> String {
+        self.chat_model.clone()
+    }
+
+    async fn summarize(&self, _model: &str, text: &str, params: SummarizeParams) -> Result<String> {
+        let prompt = format!(
+            "Write a summary of at least {} and at most {} tokens.\n\n{}",
+            params.min_tokens, params.max_tokens, text
+        );
+        self.chat_completion(Some(SUMMARY_SYSTEM_PROMPT), &prompt, params.max_tokens)
+            .await
+    }
+
+    async fn generate(&self, _model: &str, prompt: &str) -> Result<String> {
+        self.chat_completion(None, prompt, 1000).await
+    }
+
+    async fn translate(
+        &self,
+        _model: &str,
+        text: &str,
+        source: &LanguageProfile,
+        target: &LanguageProfile,
+    ) -> Result<String> {
+        let prompt = format!(
+            "Translate the following text from {} to {}.\n\n{}",
+            source.name, target.name, text
+        );
+        self.chat_completion(
+            Some(TRANSLATION_SYSTEM_PROMPT),
+            &prompt,
+            MAX_TRANSLATION_TOKENS,
+        )
+        .await
+    }
+
+    // OpenAI speech voices are multilingual, so each is offered for every language
+    fn voices(&self, languages: &Languages) -> Vec<Voice> {
+        let mut speakers: Vec<(&str, &str, &str)> = VOICES.to_vec();
+        if !speakers.iter().any(|(name, _, _)| *name == self.tts_voice) {
+            speakers.push((&self.tts_voice, "unspecified", "Configured server voice"));
+        }
+
+        languages
+            .codes()
+            .into_iter()
+            .flat_map(|language| {
+                speakers
+                    .iter()
+                    .map(move |(name, gender, description)| Voice {
+                        id: name.to_string(),
+                        language: language.to_string(),
+                        model: self.tts_model.clone(),
+                        speaker_id: Some(name.to_string()),
+                        gender: gender.to_string(),
+                        description: description.to_string(),
+                        is_default: *name == self.tts_voice,
+                    })
+            })
+            .collect()
+    }
+
+    async fn synthesize(&self, _model: &str, speaker: Option<&str>, text: &str) -> Result<Vec<u8>> {
+        let payload = json!({
+            "model": self.tts_model,
+            "input": text,
+            "voice": speaker.unwrap_or(&self.tts_voice),
+            "response_format": "wav",
+        });
+
+        let response = self
+            .post("/v1/audio/speech")
+            .json(&payload)
+            .timeout(Duration::from_secs(120))
+            .send()
+            .await
+            .map_err(|e| {
+                AppError::ExternalApi(format!("Failed to connect to speech API: {}", e))
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(AppError::ExternalApi(format!(
+                "Speech API error {}: {}",
+                status, error_text
+            )));
+        }
+
+        let bytes = response.bytes().await.map_err(|e| {
+            AppError::ExternalApi(format!("Failed to read speech response bytes: {}", e))
+        })?;
+
+        Ok(bytes.to_vec())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    content: Option<String>,
+}
@@ -0,0 +1,396 @@
+use chrono::Utc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::{
+    models::{AudioFile, AudioResponse, CreateAudioFile, Summary, Voice},
+    services::storage::content_key,
+    utils::{
+        audio,
+        errors::{AppError, Result},
+    },
+    AppState,
+};
+
+const RETRY_POLL_INTERVAL: Duration = Duration::from_secs(60);
+const RETRY_BATCH_SIZE: i64 = 5;
+// Claimed rows are hidden from other instances for this long while they are retried
+const RETRY_LEASE_SECONDS: i64 = 900;
+const RETRY_BASE_DELAY_SECONDS: i64 = 300;
+const RETRY_MAX_DELAY_SECONDS: i64 = 86_400;
+/// Longest wait for another instance synthesizing the same audio
+const AUDIO_LOCK_TIMEOUT: Duration = Duration::from_secs(300);
+
+pub fn audio_request_key(summary_id: Uuid, language: &str, voice_id: &str) -> String {
+    format!("audio:{}:{}:{}", summary_id, language, voice_id)
+}
+
+/// Delay before the next synthesis attempt after `attempts` failed retries:
+/// five minutes, doubling each time, capped at a day
+fn retry_delay(attempts: i32) -> chrono::Duration {
+    let factor = 1i64 << attempts.clamp(0, 16);
+    chrono::Duration::seconds((RETRY_BASE_DELAY_SECONDS * factor).min(RETRY_MAX_DELAY_SECONDS))
+}
+
+/// Periodically retry real speech synthesis for audio stored as a placeholder tone
+pub fn spawn_audio_retry_worker(state: AppState) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(RETRY_POLL_INTERVAL).await;
+
+            let due = match state
+                .db
+                .claim_due_placeholder_audio(RETRY_BATCH_SIZE, RETRY_LEASE_SECONDS)
+                .await
+            {
+                Ok(due) => due,
+                Err(e) => {
+                    tracing::warn!("Failed to load placeholder audio due for retry: {}", e);
+                    continue;
+                }
+            };
+
+            for placeholder in due {
+                retry_placeholder(&state, placeholder).await;
+            }
+        }
+    });
+}
+
+async fn retry_placeholder(state: &AppState, placeholder: AudioFile) {
+    tracing::info!(
+        "Retrying speech synthesis for placeholder audio {} (attempt {})",
+        placeholder.id,
+        placeholder.retry_count + 1
+    );
+
+    let result = async {
+        let summary = state
+            .db
+            .get_summary_by_id(placeholder.summary_id)
+            .await?
+            .ok_or(AppError::SummaryNotFound)?;
+        let voice = state
+            .tts
+            .resolve_voice(&placeholder.language, Some(&placeholder.voice_type))?;
+        let key = audio_request_key(summary.id, &placeholder.language, &voice.id);
+
+        state
+            .audio_requests
+            .run(&key, || {
+                generate_audio(state, &summary, &placeholder.language, &voice)
+            })
+            .await
+    }
+    .await;
+
+    match result {
+        Ok(audio) if !audio.is_placeholder => {
+            tracing::info!(
+                "Replaced placeholder audio {} with real speech {}",
+                placeholder.id,
+                audio.id
+            );
+        }
+        // `generate_audio` already rescheduled the placeholder it fell back to
+        Ok(_) => {}
+        Err(e) => {
+            tracing::warn!(
+                "Retry of placeholder audio {} failed: {}",
+                placeholder.id,
+                e
+            );
+            let delay = retry_delay(placeholder.retry_count + 1);
+            if let Err(e) = state
+                .db
+                .reschedule_placeholder_audio(placeholder.id, Utc::now() + delay)
+                .await
+            {
+                tracing::error!(
+                    "Failed to reschedule placeholder audio {}: {}",
+                    placeholder.id,
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// Voice types an existing row may carry for `voice`; audio made before the voice
+/// catalog existed is stored as `default` and belongs to each language's default voice
+pub fn stored_voice_types(voice: &Voice) -> Vec<String> {
+    let mut voice_types = vec![voice.id.clone()];
+    if voice.is_default {
+        voice_types.push("default".to_string());
+    }
+    voice_types
+}
+
+/// Generate, encode and store audio for a summary while holding the cross-instance lock.
+///
+/// When every TTS model fails the placeholder tone is stored flagged as such and
+/// scheduled for retry; a summary keeps at most one placeholder per language and voice.
+/// Real audio replaces any placeholder.
+pub async fn generate_audio(
+    state: &AppState,
+    summary: &Summary,
+    language: &str,
+    voice: &Voice,
+) -> Result<AudioResponse> {
+    let voice_types = stored_voice_types(voice);
+    let lock = state
+        .db
+        .advisory_lock(
+            &audio_request_key(summary.id, language, &voice.id),
+            AUDIO_LOCK_TIMEOUT,
+        )
+        .await?;
+
+    // Another instance may have finished the same audio while we waited for the lock
+    if let Some(existing_audio) = state
+        .db
+        .get_audio_by_summary(summary.id, language, &voice_types)
+        .await?
+    {
+        lock.release().await?;
+        return Ok(existing_audio.into_response());
+    }
+
+    tracing::info!(
+        "No existing audio found, generating new audio for summary: {} in language: {}",
+        summary.id,
+        language
+    );
+
+    let text = summary.spoken_text();
+
+    // Validate summary has content for audio generation
+    if text.trim().is_empty() {
+        tracing::error!("Summary has no text content for audio generation");
+        return Err(AppError::InvalidInput(
+            "Summary contains no text for audio generation".to_string(),
+        ));
+    }
+
+    tracing::info!(
+        "Summary text preview (first 100 chars): {}",
+        &text.chars().take(100).collect::<String>()
+    );
+    tracing::info!(
+        "Summary text length: {} characters for audio generation",
+        text.len()
+    );
+
+    tracing::info!(
+        "Starting TTS generation for summary {} in language {} with {} characters",
+        summary.id,
+        language,
+        text.len()
+    );
+
+    let synthesized = match state.tts.generate_audio(text, language, voice).await {
+        Ok(synthesized) => {
+            if synthesized.data.is_empty() {
+                tracing::error!(
+                    "TTS service returned empty audio data for summary {}",
+                    summary.id
+                );
+                return Err(AppError::ServiceError(
+                    "Audio generation completed but produced no audio data. Please try again."
+                        .to_string(),
+                ));
+            }
+
+            tracing::info!(
+                "✓ Successfully generated {}audio: {} bytes for summary {} in language {}",
+                if synthesized.is_placeholder {
+                    "placeholder "
+                } else {
+                    ""
+                },
+                synthesized.data.len(),
+                summary.id,
+                language
+            );
+            synthesized
+        }
+        Err(e) => {
+            tracing::error!("==========================================");
+            tracing::error!(
+                "✗ TTS generation FAILED for summary {} in language {}: {}",
+                summary.id,
+                language,
+                e
+            );
+            tracing::error!("==========================================");
+
+            // Provide more user-friendly error messages based on error type
+            let user_error = match e {
+                AppError::ExternalApi(ref msg) => {
+                    tracing::error!("External API error details: {}", msg);
+
+                    if msg.contains("Authentication") || msg.contains("401") || msg.contains("403")
+                    {
+                        "Audio service authentication failed. HuggingFace API token may be invalid or expired. Please check your HF_TOKEN configuration.".to_string()
+                    } else if msg.contains("timeout") || msg.contains("timed out") {
+                        "Audio generation timed out. The HuggingFace model may be loading (cold start). Please try again in a moment."
+                            .to_string()
+                    } else if msg.contains("rate limit") || msg.contains("429") {
+                        "Audio service rate limit exceeded. Please wait a moment and try again."
+                            .to_string()
+                    } else if msg.contains("model") || msg.contains("404") {
+                        format!("Audio generation model not found. Error: {}", msg)
+                    } else if msg.contains("Unable to generate audio") {
+                        format!("TTS generation failed: {}", msg)
+                    } else {
+                        format!("Audio generation service error: {}", msg)
+                    }
+                }
+                _ => format!("Audio generation failed: {}", e),
+            };
+
+            tracing::error!("Returning error to user: {}", user_error);
+            return Err(AppError::ServiceError(user_error));
+        }
+    };
+
+    // Retrying placeholders share one row instead of piling up copies of the tone.
+    // The failed attempt counts as a retry, so the row is not due again right away and
+    // later requests are served the placeholder instead of hitting the backend.
+    if synthesized.is_placeholder {
+        if let Some(placeholder) = state
+            .db
+            .get_placeholder_audio(summary.id, language, &voice_types)
+            .await?
+        {
+            let next_retry_at = Utc::now() + retry_delay(placeholder.retry_count + 1);
+            let placeholder = state
+                .db
+                .reschedule_placeholder_audio(placeholder.id, next_retry_at)
+                .await?;
+            lock.release().await?;
+            return Ok(placeholder.into_response());
+        }
+    }
+
+    let audio_data = synthesized.data;
+    let file_size_kb = (audio_data.len() / 1024) as i32;
+
+    let info = match audio::probe(&audio_data) {
+        Ok(info) => info,
+        Err(e) => {
+            tracing::error!(
+                "Generated audio for summary {} failed validation: {}",
+                summary.id,
+                e
+            );
+            return Err(AppError::ServiceError(
+                "Generated audio appears to be corrupted. Please try again.".to_string(),
+            ));
+        }
+    };
+
+    tracing::info!(
+        "Generated {} audio: {} Hz, {} channels, {:?} bits, {} ms",
+        info.extension,
+        info.sample_rate,
+        info.channels,
+        info.bit_depth,
+        info.duration_ms
+    );
+
+    let content_type = info.content_type;
+    let blob_key = content_key("audio", &audio_data, info.extension);
+    tracing::info!(
+        "Storing {}KB of audio in '{}' blob store as {}",
+        file_size_kb,
+        state.blobs.name(),
+        blob_key
+    );
+
+    if let Err(e) = state.blobs.put(&blob_key, &audio_data, content_type).await {
+        tracing::error!(
+            "Failed to store audio blob for summary {}: {}",
+            summary.id,
+            e
+        );
+        return Err(AppError::ServiceError(
+            "Audio was generated successfully but failed to save. Please try again.".to_string(),
+        ));
+    }
+
+    let create_audio = CreateAudioFile {
+        summary_id: summary.id,
+        language: language.to_string(),
+        voice_type: voice.id.clone(),
+        blob_key,
+        content_type: content_type.to_string(),
+        duration_ms: i32::try_from(info.duration_ms).ok(),
+        file_size_kb: Some(file_size_kb),
+        sample_rate: i32::try_from(info.sample_rate).ok(),
+        channels: i16::try_from(info.channels).ok(),
+        bit_depth: info.bit_depth.and_then(|bits| i16::try_from(bits).ok()),
+        is_placeholder: synthesized.is_placeholder,
+        next_retry_at: synthesized
+            .is_placeholder
+            .then(|| Utc::now() + retry_delay(0)),
+        timings: serde_json::to_value(&synthesized.timings)?,
+    };
+
+    let audio_file = match state.db.create_audio_file(create_audio).await {
+        Ok(file) => {
+            tracing::info!(
+                "Successfully saved audio file for summary: {} in language: {}",
+                summary.id,
+                language
+            );
+            file
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to save audio file to database for summary {}: {}",
+                summary.id,
+                e
+            );
+            return Err(AppError::ServiceError(
+                "Audio was generated successfully but failed to save. Please try again."
+                    .to_string(),
+            ));
+        }
+    };
+
+    if !audio_file.is_placeholder {
+        match state
+            .db
+            .delete_placeholder_audio(summary.id, language, &voice_types)
+            .await
+        {
+            Ok(blob_keys) => {
+                for key in blob_keys {
+                    if let Err(e) = state.blobs.delete(&key).await {
+                        tracing::warn!("Failed to delete placeholder audio blob {}: {}", key, e);
+                    }
+                }
+            }
+            Err(e) => tracing::warn!(
+                "Failed to remove placeholder audio for summary {}: {}",
+                summary.id,
+                e
+            ),
+        }
+    }
+
+    let response = audio_file.into_response();
+
+    tracing::info!("==========================================");
+    tracing::info!(
+        "✓ Audio generation completed successfully - Summary: {}, Language: {}, Size: {}KB",
+        summary.id,
+        language,
+        file_size_kb
+    );
+    tracing::info!("==========================================");
+
+    lock.release().await?;
+    Ok(response)
+}
@@ -0,0 +1,49 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::models::SummaryEvent;
+
+const EVENT_BUFFER: usize = 64;
+
+/// Per-job broadcast channels for live progress events.
+///
+/// A channel exists from the moment a job is enqueued until its worker finishes,
+/// so subscribers that arrive later fall back to the persisted job row.
+#[derive(Clone, Default)]
+pub struct JobEvents {
+    channels: Arc<Mutex<HashMap<Uuid, broadcast::Sender<SummaryEvent>>>>,
+}
+
+impl JobEvents {
+    pub fn open(&self, job_id: Uuid) {
+        self.channels
+            .lock()
+            .unwrap()
+            .entry(job_id)
+            .or_insert_with(|| broadcast::channel(EVENT_BUFFER).0);
+    }
+
+    pub fn subscribe(&self, job_id: Uuid) -> Option<broadcast::Receiver<SummaryEvent>> {
+        self.channels
+            .lock()
+            .unwrap()
+            .get(&job_id)
+            .map(|sender| sender.subscribe())
+    }
+
+    pub fn publish(&self, job_id: Uuid, event: SummaryEvent) {
+        if let Some(sender) = self.channels.lock().unwrap().get(&job_id) {
+            // No subscribers is fine; events are only for live listeners
+            let _ = sender.send(event);
+        }
+    }
+
+    /// Drop the channel; subscribers see the stream end after draining buffered events
+    pub fn close(&self, job_id: Uuid) {
+        self.channels.lock().unwrap().remove(&job_id);
+    }
+}
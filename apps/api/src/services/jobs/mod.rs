@@ -0,0 +1,11 @@
+mod audio;
+mod events;
+mod queue;
+mod summary;
+mod translation;
+
+pub use audio::{audio_request_key, generate_audio, spawn_audio_retry_worker, stored_voice_types};
+pub use events::JobEvents;
+pub use queue::{spawn_summary_workers, SummaryJobQueue};
+pub use summary::summary_cache_key;
+pub use translation::translate_summary;
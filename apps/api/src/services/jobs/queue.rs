@@ -0,0 +1,67 @@
+use std::sync::Arc;
+use tokio::sync::{
+    mpsc::{self, UnboundedReceiver, UnboundedSender},
+    Mutex,
+};
+use uuid::Uuid;
+
+use crate::{
+    services::jobs::{summary::run_summary_job, JobEvents},
+    utils::errors::{AppError, Result},
+    AppState,
+};
+
+/// Hands summary job IDs to the background worker pool.
+///
+/// Job state lives in the `summary_jobs` table; the queue only carries IDs, so jobs
+/// left unfinished by a previous process can simply be enqueued again on startup.
+#[derive(Clone)]
+pub struct SummaryJobQueue {
+    sender: UnboundedSender<Uuid>,
+    events: JobEvents,
+}
+
+impl SummaryJobQueue {
+    pub fn new() -> (Self, UnboundedReceiver<Uuid>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let events = JobEvents::default();
+        (Self { sender, events }, receiver)
+    }
+
+    pub fn events(&self) -> &JobEvents {
+        &self.events
+    }
+
+    pub fn enqueue(&self, job_id: Uuid) -> Result<()> {
+        self.events.open(job_id);
+        self.sender
+            .send(job_id)
+            .map_err(|_| AppError::ServiceError("Summary worker pool is not running".to_string()))
+    }
+}
+
+/// Start `workers` background tasks that process queued summary jobs
+pub fn spawn_summary_workers(state: AppState, receiver: UnboundedReceiver<Uuid>, workers: usize) {
+    let receiver = Arc::new(Mutex::new(receiver));
+
+    for worker_id in 1..=workers.max(1) {
+        let state = state.clone();
+        let receiver = receiver.clone();
+
+        tokio::spawn(async move {
+            tracing::info!("Summary worker {} started", worker_id);
+
+            loop {
+                let job_id = receiver.lock().await.recv().await;
+                let Some(job_id) = job_id else {
+                    break;
+                };
+
+                tracing::info!("Summary worker {} picked up job {}", worker_id, job_id);
+                run_summary_job(&state, job_id).await;
+            }
+
+            tracing::info!("Summary worker {} stopped", worker_id);
+        });
+    }
+}
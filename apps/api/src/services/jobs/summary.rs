@@ -0,0 +1,649 @@
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tokio::{sync::mpsc, time::timeout};
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        Book, BookDetail, ContentOrigin, CreateSummary, CreateSummaryChapter, JobStatus, Summary,
+        SummaryEvent, SummaryJob,
+    },
+    services::{
+        huggingface::{summarizer::ChapterSummary, translator::Translation, SummaryProgress},
+        jobs::JobEvents,
+        storage::DatabaseService,
+    },
+    utils::{
+        errors::{AppError, Result},
+        langid,
+    },
+    AppState,
+};
+
+pub fn summary_cache_key(
+    book_id: &str,
+    max_pages: usize,
+    style: &str,
+    language: &str,
+    format: &str,
+) -> String {
+    format!(
+        "summary:{}:{}:{}:{}:{}",
+        book_id, max_pages, style, language, format
+    )
+}
+
+/// Hash of the summarized text and request options, for reusing identical summaries
+fn compute_source_hash(text: &str, job: &SummaryJob, format: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(text.as_bytes());
+    hasher.update(job.language.as_bytes());
+    hasher.update(job.style.as_bytes());
+    hasher.update(job.max_pages.to_be_bytes());
+    hasher.update(format.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Run a queued summary job to completion, recording the outcome on the job row
+pub async fn run_summary_job(state: &AppState, job_id: Uuid) {
+    process_summary_job(state, job_id).await;
+    state.summary_jobs.events().close(job_id);
+}
+
+async fn process_summary_job(state: &AppState, job_id: Uuid) {
+    let events = state.summary_jobs.events();
+
+    // Unfinished jobs are re-enqueued by every instance on startup; only one may run each
+    let lock = match state
+        .db
+        .try_advisory_lock(&format!("summary-job:{}", job_id))
+        .await
+    {
+        Ok(Some(lock)) => lock,
+        Ok(None) => {
+            tracing::info!(
+                "Summary job {} is running on another instance, skipping",
+                job_id
+            );
+            return;
+        }
+        Err(e) => {
+            tracing::error!("Failed to lock summary job {}: {}", job_id, e);
+            return;
+        }
+    };
+
+    let job = match state.db.get_summary_job(job_id).await {
+        Ok(Some(job)) => job,
+        Ok(None) => {
+            tracing::warn!("Summary job {} not found, skipping", job_id);
+            return;
+        }
+        Err(e) => {
+            tracing::error!("Failed to load summary job {}: {}", job_id, e);
+            return;
+        }
+    };
+
+    if job.status == JobStatus::Done.as_str() || job.status == JobStatus::Failed.as_str() {
+        tracing::debug!("Summary job {} already {}, skipping", job.id, job.status);
+        return;
+    }
+
+    match generate_summary(state, &job).await {
+        Ok(summary) => {
+            tracing::info!(
+                "Summary job {} finished with summary {} for book: {}",
+                job.id,
+                summary.id,
+                job.book_id
+            );
+            if let Err(e) = state.db.complete_summary_job(job.id, summary.id).await {
+                tracing::error!("Failed to mark summary job {} as done: {}", job.id, e);
+            }
+
+            // A structured job that fell back to plain is cached as the plain summary it is
+            let cache_key = summary_cache_key(
+                &job.book_id,
+                job.max_pages as usize,
+                &job.style,
+                &job.language,
+                &summary.format,
+            );
+            let response = summary.into_response();
+            state.cache.set_json(cache_key, &response).await;
+
+            events.publish(
+                job.id,
+                SummaryEvent::Status {
+                    status: JobStatus::Done,
+                },
+            );
+            events.publish(
+                job.id,
+                SummaryEvent::Completed {
+                    summary: Box::new(response),
+                },
+            );
+        }
+        Err(e) => {
+            tracing::error!("Summary job {} failed: {}", job.id, e);
+            if let Err(db_err) = state.db.fail_summary_job(job.id, &e.to_string()).await {
+                tracing::error!(
+                    "Failed to mark summary job {} as failed: {}",
+                    job.id,
+                    db_err
+                );
+            }
+
+            events.publish(
+                job.id,
+                SummaryEvent::Status {
+                    status: JobStatus::Failed,
+                },
+            );
+            events.publish(
+                job.id,
+                SummaryEvent::Failed {
+                    error: e.to_string(),
+                },
+            );
+        }
+    }
+
+    if let Err(e) = lock.release().await {
+        tracing::warn!("Failed to release lock for summary job {}: {}", job_id, e);
+    }
+}
+
+async fn set_status(db: &DatabaseService, events: &JobEvents, job_id: Uuid, status: JobStatus) {
+    tracing::debug!("Summary job {} -> {}", job_id, status.as_str());
+    if let Err(e) = db.update_summary_job_status(job_id, status).await {
+        tracing::warn!("Failed to update summary job {} status: {}", job_id, e);
+    }
+    events.publish(job_id, SummaryEvent::Status { status });
+}
+
+async fn generate_summary(state: &AppState, job: &SummaryJob) -> Result<Summary> {
+    let book_id = &job.book_id;
+
+    tracing::info!(
+        "Processing summary job {} - Book: {}, Style: {}, Language: {}, Max Pages: {}, Format: {}",
+        job.id,
+        book_id,
+        job.style,
+        job.language,
+        job.max_pages,
+        job.format
+    );
+
+    let events = state.summary_jobs.events();
+    set_status(&state.db, events, job.id, JobStatus::FetchingContent).await;
+
+    // Get book details with timeout
+    let book_detail = timeout(
+        Duration::from_secs(30),
+        state.books.get_book_details(book_id),
+    )
+    .await
+    .map_err(|_| AppError::ServiceTimeout("Book lookup timed out".to_string()))?
+    .map_err(|e| AppError::ServiceError(format!("Failed to fetch book details: {}", e)))?
+    .ok_or_else(|| AppError::BookNotFound(format!("Book with ID {} not found", book_id)))?;
+
+    tracing::info!(
+        "Found book: '{}' by {} (source: {:?})",
+        book_detail.book.title,
+        book_detail.book.author_names(),
+        book_detail.book.source
+    );
+
+    // Get book content with improved error handling and timeout
+    let (content, content_origin) = match extract_book_content(state, &book_detail).await {
+        Ok(extracted) => extracted,
+        Err(e) => {
+            tracing::warn!("Failed to extract book content: {}, using fallback", e);
+            (
+                create_fallback_content(&book_detail.book),
+                ContentOrigin::FallbackContent,
+            )
+        }
+    };
+
+    let (text_to_summarize, content_origin) = if content.trim().is_empty() {
+        tracing::warn!(
+            "No content available for book {}, using minimal fallback",
+            book_id
+        );
+        (
+            create_minimal_fallback(&book_detail.book),
+            ContentOrigin::MinimalFallback,
+        )
+    } else {
+        (content, content_origin)
+    };
+    let input_chars = text_to_summarize.chars().count();
+
+    let detected_language =
+        detect_content_language(&book_detail.book, &text_to_summarize, content_origin);
+    let content_language = pipeline_language(state, &job.language, detected_language.as_deref())?;
+
+    let mut source_hash = compute_source_hash(&text_to_summarize, job, &job.format);
+
+    // The same text in the same language and style was summarized before
+    if !job.force_regenerate {
+        match state.db.get_summary_by_source_hash(&source_hash).await {
+            Ok(Some(existing)) if existing.is_reusable() => {
+                tracing::info!(
+                    "Reusing summary {} with matching source hash for book: {}",
+                    existing.id,
+                    book_id
+                );
+                return Ok(existing);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to look up summary by source hash: {}", e),
+        }
+    }
+
+    events.publish(
+        job.id,
+        SummaryEvent::ContentFetched {
+            characters: input_chars,
+        },
+    );
+
+    // Forward summarizer progress to the job row while it runs
+    let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+    let db = state.db.clone();
+    let forward_events = events.clone();
+    let job_id = job.id;
+    let forwarder = tokio::spawn(async move {
+        while let Some(progress) = progress_rx.recv().await {
+            match progress {
+                SummaryProgress::Chunking => {
+                    set_status(&db, &forward_events, job_id, JobStatus::Chunking).await
+                }
+                SummaryProgress::Summarizing => {
+                    set_status(&db, &forward_events, job_id, JobStatus::Summarizing).await
+                }
+                SummaryProgress::Chunked { chunks } => {
+                    forward_events.publish(job_id, SummaryEvent::Chunked { chunks })
+                }
+                SummaryProgress::ChunkSummarized {
+                    index,
+                    total,
+                    summary,
+                } => forward_events.publish(
+                    job_id,
+                    SummaryEvent::ChunkSummary {
+                        index,
+                        total,
+                        summary,
+                    },
+                ),
+            }
+        }
+    });
+
+    // The whole book is summarized, so the time limit comes from configuration
+    let summary_result = timeout(
+        Duration::from_secs(state.config.summary_timeout_seconds),
+        state.summarizer.summarize(
+            &text_to_summarize,
+            &content_language,
+            &job.style,
+            job.max_pages as usize,
+            Some(&progress_tx),
+        ),
+    )
+    .await;
+
+    drop(progress_tx);
+    let _ = forwarder.await;
+
+    let book_summary = summary_result
+        .map_err(|_| AppError::ServiceTimeout("Summary generation timed out".to_string()))?
+        .map_err(|e| {
+            tracing::error!("Summary generation failed: {}", e);
+            AppError::ServiceError(format!("Failed to generate summary: {}", e))
+        })?;
+    let mut summary_text = book_summary.summary;
+    let mut chapters = book_summary.chapters;
+    let mut model = book_summary.model;
+    let mut generation_params = book_summary.parameters;
+
+    if summary_text.trim().is_empty() {
+        return Err(AppError::ServiceError(
+            "Generated summary is empty".to_string(),
+        ));
+    }
+
+    // The content was summarized in its own language; bring the result into the requested one
+    if content_language != job.language {
+        let translation = translate_result(
+            state,
+            &summary_text,
+            &mut chapters,
+            &content_language,
+            &job.language,
+        )
+        .await?;
+        summary_text = translation.text;
+        if let serde_json::Value::Object(params) = &mut generation_params {
+            params.insert("content_language".to_string(), json!(content_language));
+            params.insert("summary_model".to_string(), json!(model));
+        }
+        model = translation.model;
+    }
+
+    // Sections are written from the finished summary, which is kept as it is. If they
+    // cannot be written, the summary is still saved as a plain one rather than lost.
+    let mut format = job.format.clone();
+    let mut structured = None;
+    let mut structured_text = None;
+    if format == "structured" {
+        let result = state
+            .structured
+            .structure(
+                &summary_text,
+                &text_to_summarize,
+                &content_language,
+                &job.language,
+            )
+            .await;
+        let params = generation_params.as_object_mut();
+        match result {
+            Ok(sections) => {
+                structured_text = Some(state.structured.render(&sections, &job.language)?);
+                structured = Some(serde_json::to_value(&sections)?);
+                if let Some(params) = params {
+                    params.insert(
+                        "structured_model".to_string(),
+                        json!(state.structured.model()),
+                    );
+                }
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Structured summary for book {} failed, saving it as plain: {}",
+                    book_id,
+                    e
+                );
+                format = "plain".to_string();
+                source_hash = compute_source_hash(&text_to_summarize, job, &format);
+                if let Some(params) = params {
+                    params.insert("structured_error".to_string(), json!(e.to_string()));
+                }
+            }
+        }
+    }
+
+    // Calculate word count and validate summary quality
+    let word_count = summary_text.split_whitespace().count() as i32;
+
+    if word_count < 10 {
+        tracing::warn!(
+            "Generated summary is very short ({} words), may be low quality",
+            word_count
+        );
+    }
+
+    tracing::info!(
+        "Generated summary with {} words for book: {}",
+        word_count,
+        book_id
+    );
+
+    set_status(&state.db, events, job.id, JobStatus::Saving).await;
+
+    // Create summary record for database
+    let create_summary = CreateSummary {
+        book_id: book_id.clone(),
+        book_title: book_detail.book.title.clone(),
+        book_author: book_detail.book.author_names(),
+        isbn: book_detail.book.isbn.clone(),
+        language: job.language.clone(),
+        summary_text,
+        word_count,
+        style: job.style.clone(),
+        source_hash,
+        method: book_summary.method,
+        content_origin,
+        input_chars: input_chars as i32,
+        truncated: book_summary.truncated,
+        model,
+        generation_params,
+        chunk_count: book_summary.chunk_count as i32,
+        translated_from: None,
+        detected_language,
+        max_pages: job.max_pages,
+        format,
+        structured,
+        structured_text,
+    };
+
+    // Save to database with timeout
+    let summary = timeout(
+        Duration::from_secs(10),
+        state.db.create_summary(create_summary),
+    )
+    .await
+    .map_err(|_| AppError::ServiceTimeout("Database save timed out".to_string()))?
+    .map_err(|e| {
+        tracing::error!("Failed to save summary to database: {}", e);
+        AppError::DatabaseError(format!("Failed to save summary: {}", e))
+    })?;
+
+    if !chapters.is_empty() {
+        let chapters = chapters
+            .into_iter()
+            .enumerate()
+            .map(|(i, chapter)| CreateSummaryChapter {
+                chapter_index: i as i32,
+                word_count: chapter.summary.split_whitespace().count() as i32,
+                title: chapter.title,
+                summary_text: chapter.summary,
+            })
+            .collect::<Vec<_>>();
+        let chapter_count = chapters.len();
+
+        match timeout(
+            Duration::from_secs(10),
+            state.db.create_summary_chapters(summary.id, chapters),
+        )
+        .await
+        {
+            Ok(Ok(())) => tracing::info!(
+                "Saved {} chapter summaries for summary {}",
+                chapter_count,
+                summary.id
+            ),
+            Ok(Err(e)) => tracing::warn!("Failed to save chapter summaries: {}", e),
+            Err(_) => tracing::warn!("Saving chapter summaries timed out"),
+        }
+    }
+
+    tracing::info!(
+        "Generated and saved summary for book: {} with style: {}",
+        book_id,
+        job.style
+    );
+
+    Ok(summary)
+}
+
+/// Identify the language of the text about to be summarized.
+///
+/// Only fetched full text is checked: provider descriptions are short blurbs, often in the
+/// catalog's language rather than the book's, and fallback content is assembled by us.
+/// Inconclusive results count as unknown.
+fn detect_content_language(book: &Book, text: &str, origin: ContentOrigin) -> Option<String> {
+    if origin != ContentOrigin::FullText {
+        return None;
+    }
+
+    let detection = langid::detect(text)?;
+    if detection.confidence < langid::MIN_CONFIDENCE {
+        tracing::info!(
+            "Content language of '{}' is inconclusive (best guess {}, confidence {:.2})",
+            book.title,
+            detection.language,
+            detection.confidence
+        );
+        return None;
+    }
+
+    tracing::info!(
+        "Detected content language {} (confidence {:.2}) for '{}'",
+        detection.language,
+        detection.confidence,
+        book.title
+    );
+    if let Some(listed) = book.language.as_deref() {
+        if !listed.eq_ignore_ascii_case(detection.language) {
+            tracing::info!(
+                "Provider lists '{}' as {}, but its content reads as {}",
+                book.title,
+                listed,
+                detection.language
+            );
+        }
+    }
+
+    Some(detection.language.to_string())
+}
+
+/// Language the summarizer should work in for content detected as `detected`.
+///
+/// Content in the requested language, or of unknown language, is summarized directly.
+/// Content in another supported language is summarized in that language and translated
+/// afterwards unless `LANGUAGE_MISMATCH=reject`. Content detected in a language without a
+/// pipeline is summarized in the requested language, since detection may be wrong and no
+/// other model could read it anyway.
+fn pipeline_language(state: &AppState, requested: &str, detected: Option<&str>) -> Result<String> {
+    let detected = match detected {
+        Some(detected) if detected != requested => detected,
+        _ => return Ok(requested.to_string()),
+    };
+
+    if state.languages.get(detected).is_none() {
+        tracing::warn!(
+            "Book content reads as '{}', which is not supported; summarizing it as {}",
+            detected,
+            requested
+        );
+        return Ok(requested.to_string());
+    }
+
+    if state.config.language_mismatch == "reject" {
+        return Err(AppError::InvalidInput(format!(
+            "Book content is in language '{}' but a '{}' summary was requested",
+            detected, requested
+        )));
+    }
+
+    tracing::info!(
+        "Summarizing {} content and translating the summary into {}",
+        detected,
+        requested
+    );
+    Ok(detected.to_string())
+}
+
+/// Translate a summary and its chapter summaries from `from` into `to`
+async fn translate_result(
+    state: &AppState,
+    summary_text: &str,
+    chapters: &mut [ChapterSummary],
+    from: &str,
+    to: &str,
+) -> Result<Translation> {
+    let translation_error =
+        |e: AppError| AppError::ServiceError(format!("Failed to translate summary: {}", e));
+
+    let translation = state
+        .translator
+        .translate(summary_text, from, to)
+        .await
+        .map_err(translation_error)?;
+
+    for chapter in chapters.iter_mut() {
+        chapter.summary = state
+            .translator
+            .translate(&chapter.summary, from, to)
+            .await
+            .map_err(translation_error)?
+            .text;
+    }
+
+    Ok(translation)
+}
+
+/// Extract content from book with proper error handling, noting where it came from
+async fn extract_book_content(
+    state: &AppState,
+    book_detail: &BookDetail,
+) -> Result<(String, ContentOrigin)> {
+    let description = || {
+        (
+            book_detail.book.description.clone().unwrap_or_default(),
+            ContentOrigin::Description,
+        )
+    };
+
+    if !book_detail.book.has_content() {
+        return Ok(description());
+    }
+
+    if let Some(ref url) = book_detail.content_url {
+        tracing::info!("Fetching book content from: {}", url);
+
+        let response = timeout(Duration::from_secs(30), state.http_client.get(url).send())
+            .await
+            .map_err(|_| AppError::ServiceTimeout("Content fetch timed out".to_string()))?
+            .map_err(|e| AppError::ServiceError(format!("Failed to fetch content: {}", e)))?;
+
+        let content = timeout(Duration::from_secs(10), response.text())
+            .await
+            .map_err(|_| AppError::ServiceTimeout("Content parsing timed out".to_string()))?
+            .map_err(|e| AppError::ServiceError(format!("Failed to parse content: {}", e)))?;
+
+        if content.len() > 1000 {
+            tracing::info!(
+                "Successfully fetched {} characters of content",
+                content.len()
+            );
+            Ok((content, ContentOrigin::FullText))
+        } else {
+            tracing::warn!("Fetched content is very short, falling back to description");
+            Ok(description())
+        }
+    } else {
+        Ok(description())
+    }
+}
+
+/// Create fallback content when primary content extraction fails
+fn create_fallback_content(book: &Book) -> String {
+    let mut content = format!("Title: {}\nAuthor(s): {}", book.title, book.author_names());
+
+    if let Some(desc) = &book.description {
+        content.push_str(&format!("\nDescription: {}", desc));
+    }
+
+    if let Some(date) = &book.published_date {
+        content.push_str(&format!("\nPublished: {}", date));
+    }
+
+    content
+}
+
+/// Create minimal fallback when no content is available at all
+fn create_minimal_fallback(book: &Book) -> String {
+    format!(
+        "This is a book titled '{}' by {}. Unfortunately, no detailed content is available for summarization. Please note that this summary will be based on the limited information available.",
+        book.title,
+        book.author_names()
+    )
+}
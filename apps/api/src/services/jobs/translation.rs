@@ -0,0 +1,212 @@
+use futures::stream::{self, StreamExt};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+use crate::{
+    models::{ContentOrigin, CreateSummary, StructuredSummary, Summary, SummaryMethod},
+    utils::errors::{AppError, Result},
+    AppState,
+};
+
+// Fields are short, so a few are translated at once
+const FIELD_CONCURRENCY: usize = 3;
+/// Longest wait for another instance translating the same summary
+const TRANSLATION_LOCK_TIMEOUT: Duration = Duration::from_secs(300);
+
+fn translation_request_key(summary_id: uuid::Uuid, language: &str) -> String {
+    format!("translation:{}:{}", summary_id, language)
+}
+
+/// Translate a stored summary into `language` and store the result as a linked summary.
+///
+/// The source book is not summarized again: the translation keeps the source's book,
+/// style, method and content origin, and records the translation model as its model.
+/// Structured summaries also have their sections translated and rendered again.
+/// An existing translation into the same language is returned instead of a new one;
+/// the flag is true when a new translation was created.
+pub async fn translate_summary(
+    state: &AppState,
+    source: &Summary,
+    language: &str,
+) -> Result<(Summary, bool)> {
+    if source.language == language {
+        return Err(AppError::InvalidInput(format!(
+            "Summary is already in language {}",
+            language
+        )));
+    }
+
+    let lock = state
+        .db
+        .advisory_lock(
+            &translation_request_key(source.id, language),
+            TRANSLATION_LOCK_TIMEOUT,
+        )
+        .await?;
+
+    // Another request may have finished the same translation while we waited for the lock
+    if let Some(existing) = state.db.get_translation(source.id, language).await? {
+        lock.release().await?;
+        tracing::info!(
+            "Returning existing translation {} of summary {} into {}",
+            existing.id,
+            source.id,
+            language
+        );
+        return Ok((existing, false));
+    }
+
+    let source_structured = source
+        .structured
+        .clone()
+        .and_then(|structured| serde_json::from_value::<StructuredSummary>(structured).ok());
+
+    let translated = async {
+        let translation = state
+            .translator
+            .translate(&source.summary_text, &source.language, language)
+            .await?;
+        // Failed sections leave a plain translation rather than no translation at all
+        let structured = match source_structured {
+            Some(structured) => {
+                Some(translate_structured(state, structured, &source.language, language).await)
+            }
+            None => None,
+        };
+        Ok((translation, structured))
+    }
+    .await;
+
+    let (translation, structured) = translated.map_err(|e| match e {
+        AppError::InvalidInput(_) => e,
+        e => {
+            tracing::error!(
+                "Translation of summary {} into {} failed: {}",
+                source.id,
+                language,
+                e
+            );
+            AppError::ServiceError(format!("Failed to translate summary: {}", e))
+        }
+    })?;
+
+    let mut generation_params = json!({
+        "source_language": source.language,
+        "source_model": source.model,
+        "source_params": source.generation_params,
+    });
+    let structured = match structured {
+        Some(Ok(structured)) => Some(structured),
+        Some(Err(e)) => {
+            tracing::warn!(
+                "Structured sections of summary {} could not be translated into {}, saving it as plain: {}",
+                source.id,
+                language,
+                e
+            );
+            generation_params["structured_error"] = json!(e.to_string());
+            None
+        }
+        None => None,
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"translation:");
+    hasher.update(source.id.as_bytes());
+    hasher.update(language.as_bytes());
+    let source_hash = hex::encode(hasher.finalize());
+
+    let create_summary = CreateSummary {
+        book_id: source.book_id.clone(),
+        book_title: source.book_title.clone(),
+        book_author: source.book_author.clone(),
+        isbn: source.isbn.clone(),
+        language: language.to_string(),
+        word_count: translation.text.split_whitespace().count() as i32,
+        summary_text: translation.text,
+        style: source.style.clone(),
+        source_hash,
+        method: SummaryMethod::parse(&source.method).unwrap_or(SummaryMethod::Abstractive),
+        content_origin: ContentOrigin::parse(&source.content_origin)
+            .unwrap_or(ContentOrigin::FullText),
+        input_chars: source.summary_text.chars().count() as i32,
+        truncated: source.truncated,
+        model: translation.model,
+        generation_params,
+        chunk_count: source.chunk_count,
+        format: if structured.is_some() {
+            source.format.clone()
+        } else {
+            "plain".to_string()
+        },
+        structured_text: structured
+            .as_ref()
+            .map(|structured| state.structured.render(structured, language))
+            .transpose()?,
+        structured: structured.map(serde_json::to_value).transpose()?,
+        translated_from: Some(source.id),
+        detected_language: source.detected_language.clone(),
+        max_pages: source.max_pages,
+    };
+
+    let summary = state.db.create_summary(create_summary).await.map_err(|e| {
+        tracing::error!("Failed to save translation of summary {}: {}", source.id, e);
+        AppError::DatabaseError(format!("Failed to save translation: {}", e))
+    })?;
+
+    tracing::info!(
+        "Translated summary {} from {} into {} as {}",
+        source.id,
+        source.language,
+        language,
+        summary.id
+    );
+
+    lock.release().await?;
+    Ok((summary, true))
+}
+
+/// Translate the prose sections of a structured summary.
+///
+/// Each field is translated on its own and put back by position, so a model answer that
+/// spans several lines cannot shift fields; character names and quotes stay as written,
+/// and empty fields are left empty without asking the translator.
+async fn translate_structured(
+    state: &AppState,
+    mut structured: StructuredSummary,
+    from: &str,
+    to: &str,
+) -> Result<StructuredSummary> {
+    let mut fields = vec![&mut structured.overview];
+    fields.extend(structured.plot_arc.iter_mut());
+    fields.extend(
+        structured
+            .characters
+            .iter_mut()
+            .map(|character| &mut character.description),
+    );
+    fields.extend(structured.themes.iter_mut());
+    fields.push(&mut structured.reading_level);
+    fields.retain(|field| !field.trim().is_empty());
+
+    let tasks: Vec<_> = fields
+        .iter()
+        .map(|field| state.translator.translate(field.as_str(), from, to))
+        .collect();
+    let translations = stream::iter(tasks)
+        .buffered(FIELD_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+
+    for (field, translation) in fields.into_iter().zip(translations) {
+        // Sections are single lines, whatever line breaks the model added
+        *field = translation?
+            .text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+
+    Ok(structured)
+}
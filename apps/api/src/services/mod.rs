@@ -1,4 +1,7 @@
 pub mod books;
 pub mod cache;
+pub mod coalesce;
 pub mod huggingface;
+pub mod inference;
+pub mod jobs;
 pub mod storage;
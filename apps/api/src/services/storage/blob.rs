@@ -0,0 +1,103 @@
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use sha2::{Digest, Sha256};
+
+use crate::{services::storage::DatabaseService, utils::errors::Result};
+
+const BACKFILL_BATCH_SIZE: i64 = 20;
+const BACKFILL_LOCK_KEY: &str = "backfill:inline_audio";
+
+/// Storage for binary artifacts such as generated audio.
+///
+/// Keys come from `content_key`, so writing the same bytes twice is harmless and a
+/// stored object is never modified after it is written.
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Short name used in logs and health output
+    fn name(&self) -> &str;
+
+    async fn put(&self, key: &str, data: &[u8], content_type: &str) -> Result<()>;
+
+    /// Fetch an object, or `None` when nothing is stored under `key`
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    async fn delete(&self, key: &str) -> Result<()>;
+}
+
+/// Content-addressed key: `<prefix>/<first two hash chars>/<sha256>.<extension>`
+pub fn content_key(prefix: &str, data: &[u8], extension: &str) -> String {
+    let hash = hex::encode(Sha256::digest(data));
+    format!("{}/{}/{}.{}", prefix, &hash[..2], hash, extension)
+}
+
+/// Split a `data:<mime>;base64,<payload>` URL into its content type and decoded bytes
+pub fn decode_data_url(url: &str) -> Option<(String, Vec<u8>)> {
+    let (meta, payload) = url.strip_prefix("data:")?.split_once(',')?;
+    let content_type = meta.strip_suffix(";base64")?;
+    let data = general_purpose::STANDARD.decode(payload).ok()?;
+
+    let content_type = if content_type.is_empty() {
+        "application/octet-stream"
+    } else {
+        content_type
+    };
+    Some((content_type.to_string(), data))
+}
+
+/// One-shot backfill that moves audio still stored inline as base64 data URLs (from
+/// before `008_audio_blobs.sql`) into the blob store.
+///
+/// It is started on every boot, but only one instance at a time runs it; the others
+/// skip it while the advisory lock is held. Rows are handled in small batches and each
+/// one is committed on its own, so the copy can be interrupted and resumed on the next
+/// start. Once every row has moved, a run is a single indexed query. Returns the number
+/// of rows moved.
+pub async fn backfill_inline_audio(db: &DatabaseService, blobs: &dyn BlobStore) -> Result<usize> {
+    let Some(lock) = db.try_advisory_lock(BACKFILL_LOCK_KEY).await? else {
+        tracing::debug!("Another instance is backfilling inline audio");
+        return Ok(0);
+    };
+
+    let moved = copy_inline_audio(db, blobs).await;
+    lock.release().await?;
+    moved
+}
+
+async fn copy_inline_audio(db: &DatabaseService, blobs: &dyn BlobStore) -> Result<usize> {
+    let mut moved = 0;
+    let mut skipped = Vec::new();
+
+    loop {
+        let rows = db
+            .get_inline_audio_files(&skipped, BACKFILL_BATCH_SIZE)
+            .await?;
+        if rows.is_empty() {
+            break;
+        }
+
+        for (id, file_url) in rows {
+            let Some((content_type, data)) = decode_data_url(&file_url) else {
+                tracing::warn!("Audio {} has an unreadable data URL, leaving it inline", id);
+                skipped.push(id);
+                continue;
+            };
+
+            let key = content_key("audio", &data, extension_for(&content_type));
+            blobs.put(&key, &data, &content_type).await?;
+            db.set_audio_blob(id, &key, &content_type).await?;
+            moved += 1;
+        }
+    }
+
+    Ok(moved)
+}
+
+/// File extension used in keys for an audio content type
+fn extension_for(content_type: &str) -> &'static str {
+    match content_type {
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/flac" => "flac",
+        "audio/ogg" => "ogg",
+        _ => "wav",
+    }
+}
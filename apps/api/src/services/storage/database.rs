@@ -1,18 +1,78 @@
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use chrono::{DateTime, Utc};
+use sqlx::{pool::PoolConnection, postgres::PgPoolOptions, PgPool, Postgres};
+use std::time::Duration;
+use tokio::time::Instant;
 use uuid::Uuid;
 
 use crate::{
-    models::{AudioFile, CreateAudioFile, CreateSummary, Summary},
-    utils::errors::Result,
+    models::{
+        AudioFile, ChunkSummary, CreateAudioFile, CreateSummary, CreateSummaryChapter,
+        CreateSummaryJob, JobStatus, Summary, SummaryChapter, SummaryJob,
+    },
+    utils::errors::{AppError, Result},
 };
 
+/// Concurrent inserts for the same book, language and style can pick the same next
+/// version; the one that loses the race retries with the following version
+const MAX_VERSION_ATTEMPTS: usize = 3;
+const VERSION_CONSTRAINT: &str = "idx_summaries_book_lang_style_version_unique";
+/// Pauses between attempts while waiting for an advisory lock
+const LOCK_POLL_MIN: Duration = Duration::from_millis(50);
+const LOCK_POLL_MAX: Duration = Duration::from_secs(1);
+
 #[derive(Clone)]
 pub struct DatabaseService {
     pool: PgPool,
+    /// Connections that hold advisory locks, kept apart so long-held locks cannot
+    /// starve queries on the main pool
+    lock_pool: PgPool,
+}
+
+/// Session-level Postgres advisory lock, shared by every API instance.
+///
+/// The lock lives on a connection from the lock pool and is held until the guard is
+/// released. A guard dropped without being released closes its connection instead of
+/// returning it to the pool, which ends the session and frees the lock.
+pub struct AdvisoryLock {
+    conn: Option<PoolConnection<Postgres>>,
+    key: String,
+}
+
+impl AdvisoryLock {
+    pub async fn release(mut self) -> Result<()> {
+        let Some(mut conn) = self.conn.take() else {
+            return Ok(());
+        };
+
+        let unlocked = sqlx::query("SELECT pg_advisory_unlock(hashtextextended($1, 0))")
+            .bind(&self.key)
+            .execute(&mut *conn)
+            .await;
+        if let Err(e) = unlocked {
+            // The session may still hold the lock, so it must not go back to the pool
+            drop(conn.detach());
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for AdvisoryLock {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            tracing::debug!("Closing connection of unreleased lock {}", self.key);
+            drop(conn.detach());
+        }
+    }
 }
 
 impl DatabaseService {
-    pub async fn new(database_url: &str, max_connections: u32) -> Result<Self> {
+    pub async fn new(
+        database_url: &str,
+        max_connections: u32,
+        lock_connections: u32,
+    ) -> Result<Self> {
         // Sanitize URL for logging
         let sanitized_url = if database_url.contains("@") {
             let parts: Vec<&str> = database_url.split("@").collect();
@@ -48,8 +108,19 @@ impl DatabaseService {
                 e
             })?;
 
+        let lock_pool = PgPoolOptions::new()
+            .max_connections(lock_connections)
+            .acquire_timeout(std::time::Duration::from_secs(30))
+            .idle_timeout(std::time::Duration::from_secs(300))
+            .connect(database_url)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database lock pool creation failed: {}", e);
+                e
+            })?;
+
         tracing::info!("Database connection pool created successfully");
-        Ok(Self { pool })
+        Ok(Self { pool, lock_pool })
     }
 
     pub async fn run_migrations(&self) -> Result<()> {
@@ -57,16 +128,91 @@ impl DatabaseService {
         Ok(())
     }
 
+    /// Wait up to `timeout` for the advisory lock on `key` and take it.
+    ///
+    /// Waiting polls `pg_try_advisory_lock` with a growing pause in between, so a
+    /// caller only occupies a lock-pool connection while it actually holds the lock and
+    /// a crowd waiting on one key cannot exhaust the pool.
+    pub async fn advisory_lock(&self, key: &str, timeout: Duration) -> Result<AdvisoryLock> {
+        let deadline = Instant::now() + timeout;
+        let mut pause = LOCK_POLL_MIN;
+
+        loop {
+            if let Some(lock) = self.try_advisory_lock(key).await? {
+                return Ok(lock);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(AppError::ServiceTimeout(format!(
+                    "Timed out after {:?} waiting for lock {}",
+                    timeout, key
+                )));
+            }
+            tokio::time::sleep(pause.min(deadline - now)).await;
+            pause = (pause * 2).min(LOCK_POLL_MAX);
+        }
+    }
+
+    /// Take the advisory lock for `key` if nobody else holds it
+    pub async fn try_advisory_lock(&self, key: &str) -> Result<Option<AdvisoryLock>> {
+        let mut conn = self.lock_pool.acquire().await?;
+        let acquired: bool =
+            sqlx::query_scalar("SELECT pg_try_advisory_lock(hashtextextended($1, 0))")
+                .bind(key)
+                .fetch_one(&mut *conn)
+                .await?;
+
+        Ok(acquired.then(|| AdvisoryLock {
+            conn: Some(conn),
+            key: key.to_string(),
+        }))
+    }
+
     pub async fn create_summary(&self, summary: CreateSummary) -> Result<Summary> {
         let id = Uuid::new_v4();
+        let mut attempt = 1;
 
-        let record = sqlx::query_as::<_, Summary>(
+        loop {
+            let result = self.insert_summary(id, &summary).await;
+            match result {
+                Err(sqlx::Error::Database(e))
+                    if e.constraint() == Some(VERSION_CONSTRAINT)
+                        && attempt < MAX_VERSION_ATTEMPTS =>
+                {
+                    tracing::debug!(
+                        "Version of summary for book {} was taken concurrently, retrying",
+                        summary.book_id
+                    );
+                    attempt += 1;
+                }
+                result => return Ok(result?),
+            }
+        }
+    }
+
+    async fn insert_summary(
+        &self,
+        id: Uuid,
+        summary: &CreateSummary,
+    ) -> std::result::Result<Summary, sqlx::Error> {
+        sqlx::query_as::<_, Summary>(
             r#"
             INSERT INTO summaries (
                 id, book_id, book_title, book_author, isbn, language,
-                summary_text, word_count, style, source_hash
+                summary_text, word_count, style, source_hash, method,
+                content_origin, input_chars, truncated, model, generation_params,
+                chunk_count, fallback_level, translated_from, detected_language, max_pages,
+                format, structured, structured_text, version
+            )
+            VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11,
+                $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24,
+                COALESCE(
+                    (SELECT MAX(version) FROM summaries
+                     WHERE book_id = $2 AND language = $6 AND style = $9),
+                    0
+                ) + 1
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             RETURNING *
             "#,
         )
@@ -80,35 +226,103 @@ impl DatabaseService {
         .bind(summary.word_count)
         .bind(&summary.style)
         .bind(&summary.source_hash)
+        .bind(summary.method.as_str())
+        .bind(summary.content_origin.as_str())
+        .bind(summary.input_chars)
+        .bind(summary.truncated)
+        .bind(&summary.model)
+        .bind(&summary.generation_params)
+        .bind(summary.chunk_count)
+        .bind(summary.content_origin.fallback_level(summary.method))
+        .bind(summary.translated_from)
+        .bind(&summary.detected_language)
+        .bind(summary.max_pages)
+        .bind(&summary.format)
+        .bind(&summary.structured)
+        .bind(&summary.structured_text)
         .fetch_one(&self.pool)
-        .await?;
-
-        Ok(record)
+        .await
     }
 
+    /// Latest summary generated for the book with these settings; translations of
+    /// summaries in other languages are not served as generated ones
     pub async fn get_summary_by_book(
         &self,
         book_id: &str,
         language: &str,
         style: &str,
+        max_pages: i32,
+        format: &str,
     ) -> Result<Option<Summary>> {
         let record = sqlx::query_as::<_, Summary>(
             r#"
             SELECT * FROM summaries
-            WHERE book_id = $1 AND language = $2 AND style = $3
-            ORDER BY created_at DESC
+            WHERE book_id = $1 AND language = $2 AND style = $3 AND max_pages = $4
+              AND format = $5 AND translated_from IS NULL
+            ORDER BY version DESC, created_at DESC
             LIMIT 1
             "#,
         )
         .bind(book_id)
         .bind(language)
         .bind(style)
+        .bind(max_pages)
+        .bind(format)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    pub async fn get_summary_by_source_hash(&self, source_hash: &str) -> Result<Option<Summary>> {
+        let record = sqlx::query_as::<_, Summary>(
+            r#"
+            SELECT * FROM summaries
+            WHERE source_hash = $1
+            ORDER BY version DESC, created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(source_hash)
         .fetch_optional(&self.pool)
         .await?;
 
         Ok(record)
     }
 
+    /// Version history, optionally limited to one page budget and format and to
+    /// summaries that are not translations
+    pub async fn get_summary_versions(
+        &self,
+        book_id: &str,
+        language: &str,
+        style: &str,
+        max_pages: Option<i32>,
+        format: Option<&str>,
+        include_translations: bool,
+    ) -> Result<Vec<Summary>> {
+        let records = sqlx::query_as::<_, Summary>(
+            r#"
+            SELECT * FROM summaries
+            WHERE book_id = $1 AND language = $2 AND style = $3
+              AND ($4::INTEGER IS NULL OR max_pages = $4)
+              AND ($5::TEXT IS NULL OR format = $5)
+              AND ($6 OR translated_from IS NULL)
+            ORDER BY version DESC, created_at DESC
+            "#,
+        )
+        .bind(book_id)
+        .bind(language)
+        .bind(style)
+        .bind(max_pages)
+        .bind(format)
+        .bind(include_translations)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
     pub async fn get_summary_by_id(&self, id: Uuid) -> Result<Option<Summary>> {
         let record = sqlx::query_as::<_, Summary>(
             r#"
@@ -122,15 +336,133 @@ impl DatabaseService {
         Ok(record)
     }
 
+    /// Latest translation of a summary into `language`
+    pub async fn get_translation(
+        &self,
+        summary_id: Uuid,
+        language: &str,
+    ) -> Result<Option<Summary>> {
+        let record = sqlx::query_as::<_, Summary>(
+            r#"
+            SELECT * FROM summaries
+            WHERE translated_from = $1 AND language = $2
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(summary_id)
+        .bind(language)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    pub async fn create_summary_chapters(
+        &self,
+        summary_id: Uuid,
+        chapters: Vec<CreateSummaryChapter>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for chapter in chapters {
+            sqlx::query(
+                r#"
+                INSERT INTO summary_chapters (
+                    id, summary_id, chapter_index, title, summary_text, word_count
+                )
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(summary_id)
+            .bind(chapter.chapter_index)
+            .bind(&chapter.title)
+            .bind(&chapter.summary_text)
+            .bind(chapter.word_count)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn get_summary_chapters(&self, summary_id: Uuid) -> Result<Vec<SummaryChapter>> {
+        let records = sqlx::query_as::<_, SummaryChapter>(
+            r#"
+            SELECT * FROM summary_chapters
+            WHERE summary_id = $1
+            ORDER BY chapter_index
+            "#,
+        )
+        .bind(summary_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    pub async fn get_chunk_summaries(
+        &self,
+        chunk_hashes: &[String],
+        model: &str,
+        style: &str,
+        language: &str,
+    ) -> Result<Vec<ChunkSummary>> {
+        let records = sqlx::query_as::<_, ChunkSummary>(
+            r#"
+            SELECT * FROM chunk_summaries
+            WHERE chunk_hash = ANY($1) AND model = $2 AND style = $3 AND language = $4
+            "#,
+        )
+        .bind(chunk_hashes)
+        .bind(model)
+        .bind(style)
+        .bind(language)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    pub async fn save_chunk_summary(
+        &self,
+        chunk_hash: &str,
+        model: &str,
+        style: &str,
+        language: &str,
+        summary_text: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO chunk_summaries (chunk_hash, model, style, language, summary_text)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (chunk_hash, model, style, language) DO NOTHING
+            "#,
+        )
+        .bind(chunk_hash)
+        .bind(model)
+        .bind(style)
+        .bind(language)
+        .bind(summary_text)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn create_audio_file(&self, audio: CreateAudioFile) -> Result<AudioFile> {
         let id = Uuid::new_v4();
 
         let record = sqlx::query_as::<_, AudioFile>(
             r#"
             INSERT INTO audio_files (
-                id, summary_id, language, voice_type, file_url, duration_ms, file_size_kb
+                id, summary_id, language, voice_type, blob_key, content_type,
+                duration_ms, file_size_kb, sample_rate, channels, bit_depth,
+                is_placeholder, next_retry_at, timings
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             RETURNING *
             "#,
         )
@@ -138,36 +470,159 @@ impl DatabaseService {
         .bind(audio.summary_id)
         .bind(&audio.language)
         .bind(&audio.voice_type)
-        .bind(&audio.file_url)
+        .bind(&audio.blob_key)
+        .bind(&audio.content_type)
         .bind(audio.duration_ms)
         .bind(audio.file_size_kb)
+        .bind(audio.sample_rate)
+        .bind(audio.channels)
+        .bind(audio.bit_depth)
+        .bind(audio.is_placeholder)
+        .bind(audio.next_retry_at)
+        .bind(&audio.timings)
         .fetch_one(&self.pool)
         .await?;
 
         Ok(record)
     }
 
+    /// Latest real (non-placeholder) audio for a summary in a language, stored under any
+    /// of `voice_types`
     pub async fn get_audio_by_summary(
         &self,
         summary_id: Uuid,
         language: &str,
+        voice_types: &[String],
     ) -> Result<Option<AudioFile>> {
         let record = sqlx::query_as::<_, AudioFile>(
             r#"
             SELECT * FROM audio_files
-            WHERE summary_id = $1 AND language = $2
+            WHERE summary_id = $1 AND language = $2 AND voice_type = ANY($3)
+              AND NOT is_placeholder
             ORDER BY created_at DESC
             LIMIT 1
             "#,
         )
         .bind(summary_id)
         .bind(language)
+        .bind(voice_types)
         .fetch_optional(&self.pool)
         .await?;
 
         Ok(record)
     }
 
+    pub async fn get_placeholder_audio(
+        &self,
+        summary_id: Uuid,
+        language: &str,
+        voice_types: &[String],
+    ) -> Result<Option<AudioFile>> {
+        let record = sqlx::query_as::<_, AudioFile>(
+            r#"
+            SELECT * FROM audio_files
+            WHERE summary_id = $1 AND language = $2 AND voice_type = ANY($3)
+              AND is_placeholder
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(summary_id)
+        .bind(language)
+        .bind(voice_types)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// Delete placeholder audio and return the blob keys no remaining row refers to.
+    ///
+    /// Blob keys are content-addressed, so the same placeholder tone may back several
+    /// rows; only keys left without any reference are returned for deletion.
+    pub async fn delete_placeholder_audio(
+        &self,
+        summary_id: Uuid,
+        language: &str,
+        voice_types: &[String],
+    ) -> Result<Vec<String>> {
+        let keys = sqlx::query_scalar::<_, String>(
+            r#"
+            WITH deleted AS (
+                DELETE FROM audio_files
+                WHERE summary_id = $1 AND language = $2 AND voice_type = ANY($3)
+                  AND is_placeholder
+                RETURNING id, blob_key
+            )
+            SELECT DISTINCT d.blob_key FROM deleted d
+            WHERE d.blob_key IS NOT NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM audio_files a
+                  WHERE a.blob_key = d.blob_key
+                    AND a.id NOT IN (SELECT id FROM deleted)
+              )
+            "#,
+        )
+        .bind(summary_id)
+        .bind(language)
+        .bind(voice_types)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(keys)
+    }
+
+    /// Claim placeholder audio whose retry is due, pushing its next retry out by
+    /// `lease_seconds` so other instances skip it meanwhile
+    pub async fn claim_due_placeholder_audio(
+        &self,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> Result<Vec<AudioFile>> {
+        let records = sqlx::query_as::<_, AudioFile>(
+            r#"
+            UPDATE audio_files
+            SET next_retry_at = NOW() + make_interval(secs => $2)
+            WHERE id IN (
+                SELECT id FROM audio_files
+                WHERE is_placeholder AND next_retry_at <= NOW()
+                ORDER BY next_retry_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(limit)
+        .bind(lease_seconds as f64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Count a failed synthesis attempt for placeholder audio and set its next retry
+    pub async fn reschedule_placeholder_audio(
+        &self,
+        id: Uuid,
+        next_retry_at: DateTime<Utc>,
+    ) -> Result<AudioFile> {
+        let record = sqlx::query_as::<_, AudioFile>(
+            r#"
+            UPDATE audio_files
+            SET retry_count = retry_count + 1, next_retry_at = $2
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(next_retry_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
     pub async fn get_audio_by_id(&self, id: Uuid) -> Result<Option<AudioFile>> {
         let record = sqlx::query_as::<_, AudioFile>(
             r#"
@@ -181,6 +636,183 @@ impl DatabaseService {
         Ok(record)
     }
 
+    /// Audio rows whose bytes still live in `file_url`, oldest first
+    pub async fn get_inline_audio_files(
+        &self,
+        exclude: &[Uuid],
+        limit: i64,
+    ) -> Result<Vec<(Uuid, String)>> {
+        let rows = sqlx::query_as::<_, (Uuid, String)>(
+            r#"
+            SELECT id, file_url FROM audio_files
+            WHERE blob_key IS NULL AND file_url IS NOT NULL AND NOT (id = ANY($1))
+            ORDER BY created_at
+            LIMIT $2
+            "#,
+        )
+        .bind(exclude)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Point an audio row at its blob and drop the inline copy
+    pub async fn set_audio_blob(&self, id: Uuid, blob_key: &str, content_type: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE audio_files
+            SET blob_key = $2, content_type = $3, file_url = NULL
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(blob_key)
+        .bind(content_type)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn create_summary_job(&self, job: CreateSummaryJob) -> Result<SummaryJob> {
+        let id = Uuid::new_v4();
+
+        let record = sqlx::query_as::<_, SummaryJob>(
+            r#"
+            INSERT INTO summary_jobs (
+                id, book_id, language, style, max_pages, status, summary_id, force_regenerate,
+                format
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(&job.book_id)
+        .bind(&job.language)
+        .bind(&job.style)
+        .bind(job.max_pages)
+        .bind(job.status.as_str())
+        .bind(job.summary_id)
+        .bind(job.force_regenerate)
+        .bind(&job.format)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    pub async fn get_summary_job(&self, id: Uuid) -> Result<Option<SummaryJob>> {
+        let record = sqlx::query_as::<_, SummaryJob>(
+            r#"
+            SELECT * FROM summary_jobs WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// Latest unfinished job for the same request that has been updated within `stale_after_seconds`
+    pub async fn get_active_summary_job(
+        &self,
+        book_id: &str,
+        language: &str,
+        style: &str,
+        max_pages: i32,
+        format: &str,
+        stale_after_seconds: i64,
+    ) -> Result<Option<SummaryJob>> {
+        let record = sqlx::query_as::<_, SummaryJob>(
+            r#"
+            SELECT * FROM summary_jobs
+            WHERE book_id = $1 AND language = $2 AND style = $3 AND max_pages = $4
+              AND format = $5
+              AND status NOT IN ($6, $7)
+              AND updated_at > NOW() - make_interval(secs => $8)
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(book_id)
+        .bind(language)
+        .bind(style)
+        .bind(max_pages)
+        .bind(format)
+        .bind(JobStatus::Done.as_str())
+        .bind(JobStatus::Failed.as_str())
+        .bind(stale_after_seconds as f64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// Jobs that were queued or in progress, e.g. when the previous process stopped
+    pub async fn get_unfinished_summary_jobs(&self) -> Result<Vec<SummaryJob>> {
+        let records = sqlx::query_as::<_, SummaryJob>(
+            r#"
+            SELECT * FROM summary_jobs
+            WHERE status NOT IN ($1, $2)
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(JobStatus::Done.as_str())
+        .bind(JobStatus::Failed.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    pub async fn update_summary_job_status(&self, id: Uuid, status: JobStatus) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE summary_jobs SET status = $2 WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(status.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn complete_summary_job(&self, id: Uuid, summary_id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE summary_jobs SET status = $2, summary_id = $3, error = NULL WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(JobStatus::Done.as_str())
+        .bind(summary_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn fail_summary_job(&self, id: Uuid, error: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE summary_jobs SET status = $2, error = $3 WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(JobStatus::Failed.as_str())
+        .bind(error)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub fn pool(&self) -> &PgPool {
         &self.pool
     }
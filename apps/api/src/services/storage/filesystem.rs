@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+use std::{
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+};
+use tokio::fs;
+use uuid::Uuid;
+
+use crate::{
+    services::storage::BlobStore,
+    utils::errors::{AppError, Result},
+};
+
+/// Blob store that keeps objects as files under a root directory
+pub struct FilesystemBlobStore {
+    root: PathBuf,
+}
+
+impl FilesystemBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path_for(&self, key: &str) -> Result<PathBuf> {
+        let relative = Path::new(key);
+        let is_plain = relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+        if key.is_empty() || !is_plain {
+            return Err(AppError::InvalidInput(format!("Invalid blob key: {}", key)));
+        }
+
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl BlobStore for FilesystemBlobStore {
+    fn name(&self) -> &str {
+        "filesystem"
+    }
+
+    async fn put(&self, key: &str, data: &[u8], _content_type: &str) -> Result<()> {
+        let path = self.path_for(key)?;
+        if fs::try_exists(&path).await? {
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        // Write to a temporary file first so readers never see a partial object
+        let temp = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+        fs::write(&temp, data).await?;
+        if let Err(e) = fs::rename(&temp, &path).await {
+            let _ = fs::remove_file(&temp).await;
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match fs::read(self.path_for(key)?).await {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match fs::remove_file(self.path_for(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
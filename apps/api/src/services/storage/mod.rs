@@ -1,3 +1,28 @@
+mod blob;
 mod database;
+mod filesystem;
+mod s3;
 
+use std::sync::Arc;
+
+pub use blob::{backfill_inline_audio, content_key, decode_data_url, BlobStore};
 pub use database::DatabaseService;
+pub use filesystem::FilesystemBlobStore;
+pub use s3::S3BlobStore;
+
+use crate::config::Settings;
+
+/// Build the blob store selected by `Settings::blob_store`
+pub fn create_blob_store(config: &Settings, http_client: reqwest::Client) -> Arc<dyn BlobStore> {
+    match config.blob_store.as_str() {
+        "s3" => Arc::new(S3BlobStore::new(
+            http_client,
+            config.s3_endpoint.clone(),
+            config.s3_bucket.clone().unwrap_or_default(),
+            config.s3_region.clone(),
+            config.s3_access_key_id.clone().unwrap_or_default(),
+            config.s3_secret_access_key.clone().unwrap_or_default(),
+        )),
+        _ => Arc::new(FilesystemBlobStore::new(config.blob_store_path.clone())),
+    }
+}
@@ -0,0 +1,183 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Method, RequestBuilder, StatusCode, Url};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+use crate::{
+    services::storage::BlobStore,
+    utils::errors::{AppError, Result},
+};
+
+const SERVICE: &str = "s3";
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+/// Blob store backed by an S3-compatible object store (AWS S3, MinIO, R2, ...).
+///
+/// Requests are signed with AWS Signature Version 4. With a custom `endpoint` the
+/// bucket is addressed path-style (`<endpoint>/<bucket>/<key>`), which is what MinIO
+/// and most S3-compatible servers expect; without one, AWS virtual-hosted addressing
+/// is used.
+pub struct S3BlobStore {
+    client: Client,
+    base_url: String,
+    region: String,
+    access_key_id: String,
+    secret_access_key: String,
+}
+
+impl S3BlobStore {
+    pub fn new(
+        client: Client,
+        endpoint: Option<String>,
+        bucket: String,
+        region: String,
+        access_key_id: String,
+        secret_access_key: String,
+    ) -> Self {
+        let base_url = match endpoint {
+            Some(endpoint) => format!("{}/{}", endpoint.trim_end_matches('/'), bucket),
+            None => format!("https://{}.s3.{}.amazonaws.com", bucket, region),
+        };
+        tracing::info!("Initializing S3 blob store at {}", base_url);
+
+        Self {
+            client,
+            base_url,
+            region,
+            access_key_id,
+            secret_access_key,
+        }
+    }
+
+    fn object_url(&self, key: &str) -> Result<Url> {
+        let path = key
+            .split('/')
+            .map(|segment| urlencoding::encode(segment).into_owned())
+            .collect::<Vec<_>>()
+            .join("/");
+        Url::parse(&format!("{}/{}", self.base_url, path))
+            .map_err(|e| AppError::InvalidInput(format!("Invalid blob key {}: {}", key, e)))
+    }
+
+    /// Build a request carrying the SigV4 `Authorization` header for `payload`
+    fn signed_request(&self, method: Method, url: Url, payload: &[u8]) -> RequestBuilder {
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex::encode(Sha256::digest(payload));
+
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method.as_str(),
+            url.path(),
+            host,
+            payload_hash,
+            amz_date,
+            signed_headers,
+            payload_hash
+        );
+
+        let scope = format!("{}/{}/{}/aws4_request", date, self.region, SERVICE);
+        let string_to_sign = format!(
+            "{}\n{}\n{}\n{}",
+            ALGORITHM,
+            amz_date,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let signing_key = [self.region.as_str(), SERVICE, "aws4_request"].iter().fold(
+            hmac_sha256(
+                format!("AWS4{}", self.secret_access_key).as_bytes(),
+                date.as_bytes(),
+            ),
+            |key, part| hmac_sha256(&key, part.as_bytes()),
+        );
+        let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+        let authorization = format!(
+            "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+            ALGORITHM, self.access_key_id, scope, signed_headers, signature
+        );
+
+        self.client
+            .request(method, url)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", amz_date)
+            .header("authorization", authorization)
+            .timeout(Duration::from_secs(60))
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+async fn error_for(operation: &str, key: &str, response: reqwest::Response) -> AppError {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    AppError::ExternalApi(format!(
+        "S3 {} of {} failed with status {}: {}",
+        operation,
+        key,
+        status,
+        body.chars().take(300).collect::<String>()
+    ))
+}
+
+#[async_trait]
+impl BlobStore for S3BlobStore {
+    fn name(&self) -> &str {
+        "s3"
+    }
+
+    async fn put(&self, key: &str, data: &[u8], content_type: &str) -> Result<()> {
+        let response = self
+            .signed_request(Method::PUT, self.object_url(key)?, data)
+            .header("content-type", content_type)
+            .body(data.to_vec())
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(error_for("upload", key, response).await);
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let response = self
+            .signed_request(Method::GET, self.object_url(key)?, &[])
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => Ok(Some(response.bytes().await?.to_vec())),
+            _ => Err(error_for("download", key, response).await),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        let response = self
+            .signed_request(Method::DELETE, self.object_url(key)?, &[])
+            .send()
+            .await?;
+
+        // S3 answers 204 whether or not the object existed
+        if !response.status().is_success() && response.status() != StatusCode::NOT_FOUND {
+            return Err(error_for("delete", key, response).await);
+        }
+        Ok(())
+    }
+}
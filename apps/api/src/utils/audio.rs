@@ -0,0 +1,311 @@
+use crate::utils::{
+    errors::{AppError, Result},
+    wav,
+};
+
+/// Container format and stream parameters read from encoded audio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioInfo {
+    pub content_type: &'static str,
+    pub extension: &'static str,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Bits per sample; `None` for lossy formats such as MP3
+    pub bit_depth: Option<u16>,
+    pub duration_ms: u64,
+}
+
+fn corrupt(format: &str, reason: impl Into<String>) -> AppError {
+    AppError::ServiceError(format!("Invalid {} audio: {}", format, reason.into()))
+}
+
+/// Identify WAV, FLAC or MP3 audio from its bytes and read its exact duration.
+///
+/// Fails for unrecognised containers, truncated headers and streams without any
+/// audio samples.
+pub fn probe(bytes: &[u8]) -> Result<AudioInfo> {
+    let info = if bytes.starts_with(b"RIFF") {
+        probe_wav(bytes)?
+    } else if bytes.starts_with(b"fLaC") {
+        probe_flac(bytes)?
+    } else if bytes.starts_with(b"ID3") || is_mp3_frame(bytes) {
+        probe_mp3(bytes)?
+    } else {
+        return Err(AppError::ServiceError(
+            "Unrecognised audio format (expected WAV, FLAC or MP3)".to_string(),
+        ));
+    };
+
+    if info.duration_ms == 0 {
+        return Err(AppError::ServiceError(
+            "Audio contains no samples".to_string(),
+        ));
+    }
+
+    Ok(info)
+}
+
+fn probe_wav(bytes: &[u8]) -> Result<AudioInfo> {
+    let parsed = wav::parse(bytes)?;
+    Ok(AudioInfo {
+        content_type: "audio/wav",
+        extension: "wav",
+        sample_rate: parsed.format.sample_rate,
+        channels: parsed.format.channels,
+        bit_depth: Some(parsed.format.bits_per_sample),
+        duration_ms: parsed.duration_ms(),
+    })
+}
+
+/// Read the mandatory STREAMINFO block that follows the `fLaC` marker
+fn probe_flac(bytes: &[u8]) -> Result<AudioInfo> {
+    const STREAMINFO: usize = 8;
+    if bytes.len() < STREAMINFO + 18 {
+        return Err(corrupt("FLAC", "truncated STREAMINFO block"));
+    }
+    if bytes[4] & 0x7F != 0 {
+        return Err(corrupt("FLAC", "first metadata block is not STREAMINFO"));
+    }
+
+    // 20 bits sample rate, 3 bits channels - 1, 5 bits bits-per-sample - 1, 36 bits samples
+    let mut packed = [0u8; 8];
+    packed.copy_from_slice(&bytes[STREAMINFO + 10..STREAMINFO + 18]);
+    let packed = u64::from_be_bytes(packed);
+
+    let sample_rate = (packed >> 44) as u32;
+    let channels = ((packed >> 41) & 0x7) as u16 + 1;
+    let bit_depth = ((packed >> 36) & 0x1F) as u16 + 1;
+    let total_samples = packed & 0xF_FFFF_FFFF;
+
+    if sample_rate == 0 {
+        return Err(corrupt("FLAC", "zero sample rate"));
+    }
+    if total_samples == 0 {
+        return Err(corrupt("FLAC", "unknown stream length"));
+    }
+
+    Ok(AudioInfo {
+        content_type: "audio/flac",
+        extension: "flac",
+        sample_rate,
+        channels,
+        bit_depth: Some(bit_depth),
+        duration_ms: total_samples * 1000 / sample_rate as u64,
+    })
+}
+
+const MP3_V1_BITRATES: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+const MP3_V2_BITRATES: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+/// Header fields of one MPEG Layer III frame
+struct Mp3Frame {
+    length: usize,
+    samples: u64,
+    sample_rate: u32,
+    channels: u16,
+}
+
+fn is_mp3_frame(bytes: &[u8]) -> bool {
+    parse_mp3_frame(bytes).is_some()
+}
+
+fn parse_mp3_frame(bytes: &[u8]) -> Option<Mp3Frame> {
+    if bytes.len() < 4 || bytes[0] != 0xFF || bytes[1] & 0xE0 != 0xE0 {
+        return None;
+    }
+
+    let version = (bytes[1] >> 3) & 0x3; // 0 = 2.5, 2 = 2, 3 = 1
+    let layer = (bytes[1] >> 1) & 0x3; // 1 = Layer III
+    let bitrate_index = (bytes[2] >> 4) as usize;
+    let rate_index = ((bytes[2] >> 2) & 0x3) as usize;
+    let padding = ((bytes[2] >> 1) & 0x1) as usize;
+    let mode = bytes[3] >> 6;
+
+    if version == 1 || layer != 1 || bitrate_index == 0 || bitrate_index == 15 || rate_index == 3 {
+        return None;
+    }
+
+    let (bitrate, sample_rate, samples, coefficient) = match version {
+        3 => (
+            MP3_V1_BITRATES[bitrate_index],
+            [44100, 48000, 32000][rate_index],
+            1152,
+            144,
+        ),
+        2 => (
+            MP3_V2_BITRATES[bitrate_index],
+            [22050, 24000, 16000][rate_index],
+            576,
+            72,
+        ),
+        _ => (
+            MP3_V2_BITRATES[bitrate_index],
+            [11025, 12000, 8000][rate_index],
+            576,
+            72,
+        ),
+    };
+
+    Some(Mp3Frame {
+        length: (coefficient * bitrate * 1000 / sample_rate) as usize + padding,
+        samples,
+        sample_rate,
+        channels: if mode == 3 { 1 } else { 2 },
+    })
+}
+
+/// Walk every frame so variable bitrate streams get an exact duration
+fn probe_mp3(bytes: &[u8]) -> Result<AudioInfo> {
+    let mut offset = 0;
+    if bytes.starts_with(b"ID3") {
+        if bytes.len() < 10 {
+            return Err(corrupt("MP3", "truncated ID3 tag"));
+        }
+        // Tag size is stored as four 7-bit bytes, excluding the 10 byte header
+        let size = bytes[6..10]
+            .iter()
+            .fold(0usize, |size, b| (size << 7) | (*b & 0x7F) as usize);
+        let footer = if bytes[5] & 0x10 != 0 { 10 } else { 0 };
+        offset = 10 + size + footer;
+    }
+
+    let mut first: Option<(u32, u16)> = None;
+    let mut samples = 0u64;
+
+    while offset < bytes.len() {
+        let Some(frame) = parse_mp3_frame(&bytes[offset..]) else {
+            break;
+        };
+        if offset + frame.length > bytes.len() {
+            break;
+        }
+        first.get_or_insert((frame.sample_rate, frame.channels));
+        samples += frame.samples;
+        offset += frame.length;
+    }
+
+    let (sample_rate, channels) = first.ok_or_else(|| corrupt("MP3", "no Layer III frames"))?;
+
+    Ok(AudioInfo {
+        content_type: "audio/mpeg",
+        extension: "mp3",
+        sample_rate,
+        channels,
+        bit_depth: None,
+        duration_ms: samples * 1000 / sample_rate as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flac(sample_rate: u64, channels: u64, bit_depth: u64, total_samples: u64) -> Vec<u8> {
+        let mut bytes = b"fLaC".to_vec();
+        // Last metadata block, type 0 (STREAMINFO), 34 bytes long
+        bytes.extend_from_slice(&[0x80, 0, 0, 34]);
+        // Block sizes and frame sizes
+        bytes.extend_from_slice(&[0x10, 0x00, 0x10, 0x00, 0, 0, 0, 0, 0, 0]);
+        let packed =
+            sample_rate << 44 | (channels - 1) << 41 | (bit_depth - 1) << 36 | total_samples;
+        bytes.extend_from_slice(&packed.to_be_bytes());
+        // MD5 of the unencoded audio
+        bytes.extend_from_slice(&[0; 16]);
+        bytes
+    }
+
+    /// An MPEG-1 Layer III frame at 44.1 kHz with a zeroed body
+    fn mp3_frame(bitrate_index: u8, length: usize, mono: bool) -> Vec<u8> {
+        let mut frame = vec![0; length];
+        frame[..4].copy_from_slice(&[0xFF, 0xFB, bitrate_index << 4, if mono { 0xC0 } else { 0 }]);
+        frame
+    }
+
+    fn error(bytes: &[u8]) -> String {
+        match probe(bytes) {
+            Err(AppError::ServiceError(message)) => message,
+            Err(other) => panic!("unexpected error: {}", other),
+            Ok(info) => panic!("probed invalid audio: {:?}", info),
+        }
+    }
+
+    #[test]
+    fn decodes_flac_streaminfo() {
+        let info = probe(&flac(44_100, 2, 16, 88_200)).unwrap();
+
+        assert_eq!(
+            info,
+            AudioInfo {
+                content_type: "audio/flac",
+                extension: "flac",
+                sample_rate: 44_100,
+                channels: 2,
+                bit_depth: Some(16),
+                duration_ms: 2000,
+            }
+        );
+
+        let info = probe(&flac(96_000, 8, 24, 0xF_FFFF_FFFF)).unwrap();
+        assert_eq!((info.channels, info.bit_depth), (8, Some(24)));
+        assert_eq!(info.duration_ms, 0xF_FFFF_FFFF * 1000 / 96_000);
+    }
+
+    #[test]
+    fn rejects_flac_without_usable_streaminfo() {
+        assert!(error(&flac(44_100, 2, 16, 88_200)[..20]).contains("truncated STREAMINFO"));
+        assert!(error(&flac(0, 2, 16, 88_200)).contains("zero sample rate"));
+        assert!(error(&flac(44_100, 2, 16, 0)).contains("unknown stream length"));
+
+        let mut padding_first = flac(44_100, 2, 16, 88_200);
+        padding_first[4] = 0x01;
+        assert!(error(&padding_first).contains("not STREAMINFO"));
+    }
+
+    #[test]
+    fn walks_every_frame_of_a_variable_bitrate_mp3() {
+        // 128 kbps frames are 417 bytes and 320 kbps frames 1044 bytes at 44.1 kHz
+        let mut bytes = Vec::new();
+        for i in 0..10 {
+            bytes.extend(if i % 2 == 0 {
+                mp3_frame(9, 417, true)
+            } else {
+                mp3_frame(14, 1044, true)
+            });
+        }
+        let info = probe(&bytes).unwrap();
+
+        assert_eq!(info.content_type, "audio/mpeg");
+        assert_eq!((info.sample_rate, info.channels), (44_100, 1));
+        assert_eq!(info.bit_depth, None);
+        // Ten frames of 1152 samples, whatever their bitrate
+        assert_eq!(info.duration_ms, 11_520 * 1000 / 44_100);
+    }
+
+    #[test]
+    fn skips_id3_tags_and_stops_at_a_truncated_frame() {
+        // A 300 byte tag: its size is stored as syncsafe 7-bit bytes
+        let mut bytes = b"ID3\x04\x00\x00\x00\x00\x02\x2C".to_vec();
+        bytes.extend_from_slice(&[0; 300]);
+        for _ in 0..3 {
+            bytes.extend(mp3_frame(9, 417, false));
+        }
+        bytes.extend(&mp3_frame(9, 417, false)[..100]);
+        let info = probe(&bytes).unwrap();
+
+        assert_eq!(info.channels, 2);
+        assert_eq!(info.duration_ms, 3 * 1152 * 1000 / 44_100);
+    }
+
+    #[test]
+    fn rejects_unrecognised_and_empty_audio() {
+        assert!(error(b"OggS\0\x02").contains("Unrecognised audio format"));
+        assert!(error(b"ID3\x04\x00\x00\x00\x00\x00\x00").contains("no Layer III frames"));
+
+        let mut silent = b"RIFF\0\0\0\0WAVE".to_vec();
+        silent.extend_from_slice(b"fmt \x10\0\0\0\x01\0\x01\0\x40\x1F\0\0\x80\x3E\0\0\x02\0\x10\0");
+        silent.extend_from_slice(b"data\0\0\0\0");
+        assert!(error(&silent).contains("no samples"));
+    }
+}
@@ -0,0 +1,160 @@
+/// An inclusive byte range within a resource of known length
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    pub fn size(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// Value for the `Content-Range` header of a 206 response
+    pub fn content_range(&self, total: u64) -> String {
+        format!("bytes {}-{}/{}", self.start, self.end, total)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RangeRequest {
+    /// No usable `Range` header; serve the whole resource
+    Full,
+    Partial(ByteRange),
+    /// The range lies entirely past the end of the resource
+    Unsatisfiable,
+}
+
+/// Interpret a `Range` header against a resource of `total` bytes.
+///
+/// Supports `bytes=start-end`, `bytes=start-` and `bytes=-suffix`. Malformed headers,
+/// other units and multi-range requests fall back to the full resource, which RFC 9110
+/// allows servers to do.
+pub fn parse_range(header: Option<&str>, total: u64) -> RangeRequest {
+    let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return RangeRequest::Full;
+    };
+    if spec.contains(',') {
+        return RangeRequest::Full;
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return RangeRequest::Full;
+    };
+
+    let range = match (start.trim(), end.trim()) {
+        ("", "") => return RangeRequest::Full,
+        ("", suffix) => {
+            let Ok(suffix) = suffix.parse::<u64>() else {
+                return RangeRequest::Full;
+            };
+            if suffix == 0 || total == 0 {
+                return RangeRequest::Unsatisfiable;
+            }
+            ByteRange {
+                start: total.saturating_sub(suffix),
+                end: total - 1,
+            }
+        }
+        (start, end) => {
+            let Ok(start) = start.parse::<u64>() else {
+                return RangeRequest::Full;
+            };
+            let end = if end.is_empty() {
+                u64::MAX
+            } else {
+                match end.parse::<u64>() {
+                    Ok(end) if end >= start => end,
+                    _ => return RangeRequest::Full,
+                }
+            };
+            if start >= total {
+                return RangeRequest::Unsatisfiable;
+            }
+            ByteRange {
+                start,
+                end: end.min(total - 1),
+            }
+        }
+    };
+
+    RangeRequest::Partial(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partial(start: u64, end: u64) -> RangeRequest {
+        RangeRequest::Partial(ByteRange { start, end })
+    }
+
+    #[test]
+    fn parses_the_supported_forms() {
+        assert_eq!(parse_range(Some("bytes=0-99"), 1000), partial(0, 99));
+        assert_eq!(parse_range(Some("bytes=500-"), 1000), partial(500, 999));
+        assert_eq!(parse_range(Some("bytes=-100"), 1000), partial(900, 999));
+        assert_eq!(parse_range(Some(" bytes= 10 - 19 "), 1000), partial(10, 19));
+    }
+
+    #[test]
+    fn clamps_ranges_to_the_resource() {
+        assert_eq!(parse_range(Some("bytes=900-5000"), 1000), partial(900, 999));
+        assert_eq!(parse_range(Some("bytes=-5000"), 1000), partial(0, 999));
+        assert_eq!(parse_range(Some("bytes=999-999"), 1000), partial(999, 999));
+    }
+
+    #[test]
+    fn empty_suffixes_and_starts_past_the_end_are_unsatisfiable() {
+        assert_eq!(
+            parse_range(Some("bytes=-0"), 1000),
+            RangeRequest::Unsatisfiable
+        );
+        assert_eq!(
+            parse_range(Some("bytes=1000-"), 1000),
+            RangeRequest::Unsatisfiable
+        );
+        assert_eq!(
+            parse_range(Some("bytes=2000-3000"), 1000),
+            RangeRequest::Unsatisfiable
+        );
+        assert_eq!(
+            parse_range(Some("bytes=0-"), 0),
+            RangeRequest::Unsatisfiable
+        );
+        assert_eq!(
+            parse_range(Some("bytes=-10"), 0),
+            RangeRequest::Unsatisfiable
+        );
+    }
+
+    #[test]
+    fn unusable_headers_serve_the_full_resource() {
+        for header in [
+            None,
+            Some("bytes=20-10"),
+            Some("bytes=0-9,20-29"),
+            Some("items=0-9"),
+            Some("bytes=-"),
+            Some("bytes=abc-"),
+            Some("bytes=0-x"),
+            Some("bytes=10"),
+        ] {
+            assert_eq!(
+                parse_range(header, 1000),
+                RangeRequest::Full,
+                "{:?}",
+                header
+            );
+        }
+    }
+
+    #[test]
+    fn describes_the_served_range() {
+        let range = ByteRange {
+            start: 900,
+            end: 999,
+        };
+        assert_eq!(range.size(), 100);
+        assert_eq!(range.content_range(1000), "bytes 900-999/1000");
+    }
+}
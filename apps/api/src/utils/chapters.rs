@@ -0,0 +1,299 @@
+/// A chapter found in book text, with its heading and body.
+///
+/// Text before the first heading, such as a preface, has an empty `title`.
+#[derive(Debug, Clone)]
+pub struct Chapter {
+    pub title: String,
+    pub content: String,
+}
+
+// Headings with less body than this are table-of-contents entries or part dividers
+const MIN_CHAPTER_WORDS: usize = 50;
+const MAX_HEADING_WORDS: usize = 10;
+const MAX_SUBTITLE_WORDS: usize = 8;
+
+const HEADING_KEYWORDS: [&str; 4] = ["CHAPTER", "BOOK", "PART", "VOLUME"];
+
+const NUMBER_WORDS: [&str; 12] = [
+    "ONE", "TWO", "THREE", "FOUR", "FIVE", "SIX", "SEVEN", "EIGHT", "NINE", "TEN", "ELEVEN",
+    "TWELVE",
+];
+
+const ORDINAL_WORDS: [&str; 13] = [
+    "FIRST", "SECOND", "THIRD", "FOURTH", "FIFTH", "SIXTH", "SEVENTH", "EIGHTH", "NINTH", "TENTH",
+    "ELEVENTH", "TWELFTH", "LAST",
+];
+
+/// Split book text into chapters on CHAPTER / BOOK / PART headings and bare Roman numerals.
+///
+/// A bare numeral only counts as a heading on a line of its own between blank lines, so a
+/// lone "I" in verse or dialogue does not split the text. Table-of-contents entries
+/// produce headings without a body and are dropped. Text before the first heading is
+/// kept as an untitled first chapter when it is long enough to be a preface or
+/// prologue rather than a title page. Returns an empty list when the text has fewer
+/// than two titled chapters.
+pub fn segment_chapters(text: &str) -> Vec<Chapter> {
+    let lines: Vec<&str> = text.lines().map(|l| l.trim()).collect();
+    let mut chapters = Vec::new();
+    let mut current: (String, Vec<&str>) = (String::new(), Vec::new());
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+
+        if is_heading_at(&lines, i) {
+            let (title, body) = std::mem::take(&mut current);
+            push_chapter(&mut chapters, title, &body);
+
+            let mut title = line.trim_end_matches(['.', ':']).to_string();
+            // "CHAPTER I." is often followed by its name, on the next line or set apart
+            if let Some(next) = subtitle_after(&lines, i) {
+                title = format!("{}: {}", title, lines[next].trim_end_matches('.'));
+                i = next;
+            }
+
+            current = (title, Vec::new());
+        } else {
+            current.1.push(line);
+        }
+
+        i += 1;
+    }
+
+    let (title, body) = current;
+    push_chapter(&mut chapters, title, &body);
+
+    if chapters.iter().filter(|c| !c.title.is_empty()).count() < 2 {
+        return Vec::new();
+    }
+
+    chapters
+}
+
+/// Keep a chapter with enough body, with its paragraphs separated by blank lines
+fn push_chapter(chapters: &mut Vec<Chapter>, title: String, body: &[&str]) {
+    let content = body
+        .split(|line| line.is_empty())
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| paragraph.join("\n"))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if content.split_whitespace().count() >= MIN_CHAPTER_WORDS {
+        chapters.push(Chapter { title, content });
+    }
+}
+
+fn is_blank_at(lines: &[&str], i: Option<usize>) -> bool {
+    i.and_then(|i| lines.get(i))
+        .is_none_or(|line| line.is_empty())
+}
+
+/// Whether line `i` is a heading, judging bare numerals by the blank lines around them
+fn is_heading_at(lines: &[&str], i: usize) -> bool {
+    let line = lines[i];
+    if is_bare_numeral(line) {
+        return is_blank_at(lines, i.checked_sub(1)) && is_blank_at(lines, Some(i + 1));
+    }
+    is_heading(line)
+}
+
+/// Bare Roman numeral headings such as "IV." or "XII"
+fn is_bare_numeral(line: &str) -> bool {
+    !line.contains(char::is_whitespace) && is_roman_numeral(line.trim_end_matches(['.', ':']))
+}
+
+/// "CHAPTER IV", "Book 2", "PART THE SECOND" and the like, optionally with a title
+fn is_heading(line: &str) -> bool {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    if words.len() < 2 || words.len() > MAX_HEADING_WORDS {
+        return false;
+    }
+
+    let keyword = words[0];
+    let is_keyword = HEADING_KEYWORDS
+        .iter()
+        .any(|k| keyword == *k || (keyword.len() == k.len() && keyword == capitalize(k).as_str()));
+    if !is_keyword {
+        return false;
+    }
+
+    let number = words[1].trim_end_matches(['.', ':', ',']).to_uppercase();
+    if number == "THE" {
+        // "BOOK THE FIRST", alone or ending before a title
+        return words.get(2).is_some_and(|ordinal| {
+            let stands_alone = words.len() == 3 || ordinal.ends_with(['.', ':']);
+            let ordinal = ordinal.trim_end_matches(['.', ':', ',']).to_uppercase();
+            stands_alone && ORDINAL_WORDS.contains(&ordinal.as_str())
+        });
+    }
+
+    number.chars().all(|c| c.is_ascii_digit())
+        || is_roman_numeral(&number)
+        || NUMBER_WORDS.contains(&number.as_str())
+        || ORDINAL_WORDS.contains(&number.as_str())
+}
+
+/// Index of the line naming the chapter whose bare heading is on line `i`, if any.
+///
+/// The name either sits straight under the heading or is a one-line paragraph of its own.
+fn subtitle_after(lines: &[&str], i: usize) -> Option<usize> {
+    let next = (i + 1..lines.len()).find(|&j| !lines[j].is_empty())?;
+    let set_apart = next > i + 1;
+    if set_apart && !is_blank_at(lines, Some(next + 1)) {
+        return None;
+    }
+
+    is_subtitle(lines[i], lines[next], is_heading_at(lines, next)).then_some(next)
+}
+
+/// A short line after a bare "CHAPTER IV." heading that names the chapter
+fn is_subtitle(heading: &str, next: &str, next_is_heading: bool) -> bool {
+    let heading_words = heading.split_whitespace().count();
+    let next_words = next.split_whitespace().count();
+
+    heading_words <= 2
+        && next_words > 0
+        && next_words <= MAX_SUBTITLE_WORDS
+        && !next_is_heading
+        && next.chars().next().is_some_and(|c| c.is_uppercase())
+        && !next.ends_with([',', ';'])
+}
+
+/// Canonical Roman numerals only, so words like "DID" or "MILD" are not headings
+fn is_roman_numeral(word: &str) -> bool {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+
+    let value_of = |c: char| match c {
+        'I' => Some(1),
+        'V' => Some(5),
+        'X' => Some(10),
+        'L' => Some(50),
+        'C' => Some(100),
+        'D' => Some(500),
+        'M' => Some(1000),
+        _ => None,
+    };
+
+    let Some(values) = word.chars().map(value_of).collect::<Option<Vec<usize>>>() else {
+        return false;
+    };
+    if values.is_empty() {
+        return false;
+    }
+
+    let mut total = 0;
+    for (i, value) in values.iter().enumerate() {
+        match values.get(i + 1) {
+            Some(next) if next > value => total -= *value as isize,
+            _ => total += *value as isize,
+        }
+    }
+    if total <= 0 {
+        return false;
+    }
+
+    let mut remaining = total as usize;
+    let mut canonical = String::new();
+    for (value, numeral) in NUMERALS {
+        while remaining >= value {
+            canonical.push_str(numeral);
+            remaining -= value;
+        }
+    }
+
+    canonical == word
+}
+
+fn capitalize(word: &str) -> String {
+    let lower = word.to_lowercase();
+    let mut chars = lower.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(word: &str) -> String {
+        vec![word; 60].join(" ")
+    }
+
+    #[test]
+    fn splits_on_keyword_headings_and_names_them() {
+        let text = format!(
+            "CHAPTER I.\nThe Beginning\n\n{}\n\nCHAPTER II.\n\nThe End\n\n{}",
+            body("alpha"),
+            body("beta")
+        );
+        let chapters = segment_chapters(&text);
+
+        let titles: Vec<&str> = chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["CHAPTER I: The Beginning", "CHAPTER II: The End"]);
+        assert!(chapters[1].content.starts_with("beta"));
+    }
+
+    #[test]
+    fn the_needs_an_ordinal() {
+        assert!(is_heading("BOOK THE FIRST"));
+        assert!(is_heading("BOOK THE FIRST. RECALLED TO LIFE"));
+        assert!(!is_heading("Part the second half of the day was spent"));
+        assert!(!is_heading("Book the rooms early"));
+    }
+
+    #[test]
+    fn bare_numerals_need_blank_lines_around_them() {
+        let verse = format!(
+            "{}\nI\nwandered lonely as a cloud\n{}",
+            body("x"),
+            body("y")
+        );
+        assert!(segment_chapters(&verse).is_empty());
+
+        let text = format!("I\n\n{}\n\nII.\n\n{}", body("one"), body("two"));
+        let chapters = segment_chapters(&text);
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[1].title, "II");
+    }
+
+    #[test]
+    fn keeps_text_before_the_first_heading_untitled() {
+        let text = format!(
+            "PREFACE\n\n{}\n\nCHAPTER 1\n\n{}\n\nCHAPTER 2\n\n{}",
+            body("preface"),
+            body("one"),
+            body("two")
+        );
+        let chapters = segment_chapters(&text);
+
+        assert_eq!(chapters.len(), 3);
+        assert_eq!(chapters[0].title, "");
+        assert!(chapters[0].content.contains("preface"));
+    }
+
+    #[test]
+    fn drops_table_of_contents_entries() {
+        let text = format!(
+            "CHAPTER I. Arrival\nCHAPTER II. Departure\n\nCHAPTER I. Arrival\n\n{}\n\nCHAPTER II. Departure\n\n{}",
+            body("a"),
+            body("b")
+        );
+        assert_eq!(segment_chapters(&text).len(), 2);
+    }
+}
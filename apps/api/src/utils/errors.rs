@@ -29,6 +29,9 @@ pub enum AppError {
     #[error("Audio not found")]
     AudioNotFound,
 
+    #[error("Job not found")]
+    JobNotFound,
+
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
@@ -63,6 +66,7 @@ impl IntoResponse for AppError {
             AppError::BookNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
             AppError::SummaryNotFound => (StatusCode::NOT_FOUND, self.to_string()),
             AppError::AudioNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::JobNotFound => (StatusCode::NOT_FOUND, self.to_string()),
             AppError::InvalidInput(_) => (StatusCode::BAD_REQUEST, self.to_string()),
             AppError::RateLimit => (StatusCode::TOO_MANY_REQUESTS, self.to_string()),
             AppError::ContentTooLarge => (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
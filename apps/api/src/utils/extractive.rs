@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+
+use crate::utils::text::{self, SentenceRules};
+
+/// Recorded as the model of summaries produced here
+pub const MODEL_ID: &str = "textrank";
+
+/// Larger inputs are sampled evenly so the similarity graph stays tractable
+pub const MAX_GRAPH_SENTENCES: usize = 1000;
+const MIN_SENTENCE_CHARS: usize = 20;
+const DAMPING: f64 = 0.85;
+const MAX_ITERATIONS: usize = 50;
+const CONVERGENCE: f64 = 1e-5;
+
+pub struct Extract {
+    pub summary: String,
+    /// The input had more than `MAX_GRAPH_SENTENCES` sentences and only a sample was ranked
+    pub sampled: bool,
+}
+
+/// Extractive summary built entirely in-process with TextRank.
+///
+/// Sentences are weighted by TF-IDF, linked by cosine similarity and ranked with
+/// PageRank; the best ones are kept in their original order until `max_words` is
+/// reached. Sentences are split by `rules` and `stopwords` are left out of the vectors.
+/// Returns `None` when the text has no usable sentences.
+pub fn summarize(
+    text: &str,
+    max_words: usize,
+    rules: &SentenceRules,
+    stopwords: &[&str],
+) -> Option<Extract> {
+    let sentences = split_sentences(text, rules);
+    if sentences.is_empty() {
+        return None;
+    }
+
+    let sampled = sentences.len() > MAX_GRAPH_SENTENCES;
+    let sentences = sample(sentences, MAX_GRAPH_SENTENCES);
+    let vectors = tfidf_vectors(&sentences, stopwords);
+    let scores = rank(&vectors);
+
+    let mut order: Vec<usize> = (0..sentences.len()).collect();
+    order.sort_by(|a, b| scores[*b].total_cmp(&scores[*a]));
+
+    let mut selected = Vec::new();
+    let mut words = 0;
+    for index in order {
+        let sentence_words = sentences[index].split_whitespace().count();
+        if words + sentence_words > max_words && !selected.is_empty() {
+            continue;
+        }
+        selected.push(index);
+        words += sentence_words;
+        if words >= max_words {
+            break;
+        }
+    }
+
+    selected.sort_unstable();
+    let summary = selected
+        .into_iter()
+        .map(|i| sentences[i].as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Some(Extract { summary, sampled })
+}
+
+fn split_sentences(text: &str, rules: &SentenceRules) -> Vec<String> {
+    text::sentence_spans(text, rules)
+        .into_iter()
+        .map(|span| &text[span])
+        .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|s| {
+            s.len() > MIN_SENTENCE_CHARS
+                && !s.contains("Project Gutenberg")
+                && !s.starts_with("CHAPTER")
+        })
+        .collect()
+}
+
+fn sample<T>(items: Vec<T>, max: usize) -> Vec<T> {
+    if items.len() <= max {
+        return items;
+    }
+
+    let stride = items.len() as f64 / max as f64;
+    let keep: Vec<usize> = (0..max).map(|i| (i as f64 * stride) as usize).collect();
+    items
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| keep.binary_search(i).is_ok())
+        .map(|(_, item)| item)
+        .collect()
+}
+
+fn tokenize(sentence: &str, stopwords: &[&str]) -> Vec<String> {
+    sentence
+        .split(|c: char| !text::is_word_char(c))
+        .filter(|w| w.len() > 1)
+        .map(|w| w.to_lowercase())
+        .filter(|w| !stopwords.contains(&w.as_str()))
+        .collect()
+}
+
+/// Sparse, L2-normalized TF-IDF vectors sorted by term id
+fn tfidf_vectors(sentences: &[String], stopwords: &[&str]) -> Vec<Vec<(usize, f64)>> {
+    let mut vocabulary: HashMap<String, usize> = HashMap::new();
+    let mut counts: Vec<HashMap<usize, f64>> = Vec::with_capacity(sentences.len());
+
+    for sentence in sentences {
+        let mut tf = HashMap::new();
+        for token in tokenize(sentence, stopwords) {
+            let next_id = vocabulary.len();
+            let id = *vocabulary.entry(token).or_insert(next_id);
+            *tf.entry(id).or_insert(0.0) += 1.0;
+        }
+        counts.push(tf);
+    }
+
+    let mut document_frequency = vec![0.0; vocabulary.len()];
+    for tf in &counts {
+        for id in tf.keys() {
+            document_frequency[*id] += 1.0;
+        }
+    }
+
+    let total = sentences.len() as f64;
+    counts
+        .into_iter()
+        .map(|tf| {
+            let mut vector: Vec<(usize, f64)> = tf
+                .into_iter()
+                .map(|(id, count)| (id, count * (1.0 + total / document_frequency[id]).ln()))
+                .collect();
+            let norm = vector.iter().map(|(_, w)| w * w).sum::<f64>().sqrt();
+            if norm > 0.0 {
+                vector.iter_mut().for_each(|(_, w)| *w /= norm);
+            }
+            vector.sort_unstable_by_key(|(id, _)| *id);
+            vector
+        })
+        .collect()
+}
+
+fn cosine(a: &[(usize, f64)], b: &[(usize, f64)]) -> f64 {
+    let (mut i, mut j, mut dot) = (0, 0, 0.0);
+    while i < a.len() && j < b.len() {
+        match a[i].0.cmp(&b[j].0) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                dot += a[i].1 * b[j].1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    dot
+}
+
+/// PageRank over the weighted sentence similarity graph
+fn rank(vectors: &[Vec<(usize, f64)>]) -> Vec<f64> {
+    let n = vectors.len();
+    let mut edges: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let weight = cosine(&vectors[i], &vectors[j]);
+            if weight > 0.0 {
+                edges[i].push((j, weight));
+                edges[j].push((i, weight));
+            }
+        }
+    }
+
+    let out_weight: Vec<f64> = edges
+        .iter()
+        .map(|e| e.iter().map(|(_, w)| w).sum())
+        .collect();
+    let mut scores = vec![1.0 / n as f64; n];
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut next = vec![(1.0 - DAMPING) / n as f64; n];
+        for (i, neighbours) in edges.iter().enumerate() {
+            for (j, weight) in neighbours {
+                next[i] += DAMPING * scores[*j] * weight / out_weight[*j];
+            }
+        }
+
+        let delta: f64 = next.iter().zip(&scores).map(|(a, b)| (a - b).abs()).sum();
+        scores = next;
+        if delta < CONVERGENCE {
+            break;
+        }
+    }
+
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: SentenceRules = SentenceRules {
+        terminators: &['.', '!', '?'],
+        abbreviations: &[],
+    };
+    const STOPWORDS: &[&str] = &["the", "a", "of", "and", "to", "in", "on", "was"];
+
+    const TEXT: &str = "The whale swam deep in the cold sea. \
+        A sailor mended the torn nets on the deck. \
+        The captain hunted the white whale across the sea. \
+        Bread and cheese were served at noon. \
+        The white whale broke the boat of the captain at sea.";
+
+    #[test]
+    fn keeps_ranked_sentences_in_their_original_order() {
+        let extract = summarize(TEXT, 22, &RULES, STOPWORDS).unwrap();
+        let sentences = split_sentences(&extract.summary, &RULES);
+        assert!(sentences.len() > 1);
+
+        let positions: Vec<usize> = sentences
+            .iter()
+            .map(|sentence| TEXT.find(sentence.as_str()).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(extract.summary.split_whitespace().count() <= 22);
+    }
+
+    #[test]
+    fn prefers_sentences_central_to_the_text() {
+        let extract = summarize(TEXT, 11, &RULES, STOPWORDS).unwrap();
+        assert!(extract.summary.contains("whale"));
+        assert!(!extract.summary.contains("Bread and cheese"));
+    }
+
+    #[test]
+    fn keeps_a_single_sentence_whole() {
+        let text = "  Call me Ishmael,\n some years ago.  ";
+        let extract = summarize(text, 2, &RULES, STOPWORDS).unwrap();
+        assert_eq!(extract.summary, "Call me Ishmael, some years ago.");
+        assert!(!extract.sampled);
+    }
+
+    #[test]
+    fn returns_nothing_without_usable_sentences() {
+        assert!(summarize("", 100, &RULES, STOPWORDS).is_none());
+        assert!(summarize("   \n  ", 100, &RULES, STOPWORDS).is_none());
+        assert!(summarize("Too short. Also short.", 100, &RULES, STOPWORDS).is_none());
+    }
+
+    #[test]
+    fn samples_large_inputs_evenly() {
+        let sampled = sample((0..10).collect(), 5);
+        assert_eq!(sampled, vec![0, 2, 4, 6, 8]);
+        assert_eq!(sample(vec![1, 2, 3], 5), vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,297 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+/// Trigrams kept per profile, as in Cavnar and Trenkle's out-of-place measure
+const PROFILE_SIZE: usize = 300;
+/// Characters read from each of the sampled windows of a long text
+const WINDOW_CHARS: usize = 4000;
+const MIN_LETTERS: usize = 40;
+/// A script must cover this share of the letters to decide the language on its own
+const MIN_SCRIPT_SHARE: f64 = 0.5;
+/// Below this margin over the runner-up language, a detection should be treated as
+/// inconclusive. Book openings of a few hundred characters clear it against the
+/// profiles below; mixed or very short text usually does not.
+pub const MIN_CONFIDENCE: f64 = 0.1;
+
+/// Language of a text and how clearly it stood out from the alternatives (0.0 - 1.0)
+#[derive(Debug, Clone, Copy)]
+pub struct Detection {
+    pub language: &'static str,
+    pub confidence: f64,
+}
+
+/// Scripts that are written by a single language we can name without looking further
+const SCRIPTS: &[(&str, char, char)] = &[
+    ("ta", '\u{0B80}', '\u{0BFF}'),
+    ("hi", '\u{0900}', '\u{097F}'),
+    ("ru", '\u{0400}', '\u{04FF}'),
+    ("el", '\u{0370}', '\u{03FF}'),
+    ("ar", '\u{0600}', '\u{06FF}'),
+    ("zh", '\u{4E00}', '\u{9FFF}'),
+];
+
+/// Sample corpus for each Latin-script language; its trigram ranks form the profile.
+///
+/// Each corpus mixes narration, dialogue, description and formal prose so the profile
+/// is not tied to the vocabulary of a single passage.
+const SAMPLES: &[(&str, &str)] = &[
+    ("en", include_str!("langid/en.txt")),
+    ("de", include_str!("langid/de.txt")),
+    ("fr", include_str!("langid/fr.txt")),
+    ("es", include_str!("langid/es.txt")),
+    ("it", include_str!("langid/it.txt")),
+    ("nl", include_str!("langid/nl.txt")),
+    ("pt", include_str!("langid/pt.txt")),
+];
+
+/// Identify the language of `text` without leaving the process.
+///
+/// Long texts are sampled in three windows from the middle so front matter and licence
+/// boilerplate do not decide the result. Texts dominated by a single-language script
+/// (such as Tamil) are identified by script; Latin-script text is matched against
+/// character trigram profiles. Returns `None` when there are too few letters to tell.
+pub fn detect(text: &str) -> Option<Detection> {
+    let sample = sample_windows(text);
+
+    let mut letters = 0;
+    let mut script_counts = vec![0usize; SCRIPTS.len()];
+    for c in sample.chars().filter(|c| c.is_alphabetic()) {
+        letters += 1;
+        if let Some(i) = SCRIPTS
+            .iter()
+            .position(|(_, start, end)| (*start..=*end).contains(&c))
+        {
+            script_counts[i] += 1;
+        }
+    }
+
+    if letters < MIN_LETTERS {
+        return None;
+    }
+
+    if let Some((i, count)) = script_counts
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, count)| **count)
+    {
+        let share = *count as f64 / letters as f64;
+        if share >= MIN_SCRIPT_SHARE {
+            return Some(Detection {
+                language: SCRIPTS[i].0,
+                confidence: share,
+            });
+        }
+    }
+
+    let ranks = ranked_trigrams(&sample);
+    if ranks.is_empty() {
+        return None;
+    }
+
+    let mut distances: Vec<(&'static str, usize)> = profiles()
+        .iter()
+        .map(|(language, profile)| (*language, out_of_place(&ranks, profile)))
+        .collect();
+    distances.sort_by_key(|(_, distance)| *distance);
+
+    let (language, best) = distances[0];
+    let second = distances.get(1).map_or(best, |(_, d)| *d);
+    let confidence = if second == 0 {
+        0.0
+    } else {
+        (second - best) as f64 / second as f64
+    };
+
+    Some(Detection {
+        language,
+        confidence,
+    })
+}
+
+fn profiles() -> &'static Vec<(&'static str, HashMap<String, usize>)> {
+    static PROFILES: OnceLock<Vec<(&'static str, HashMap<String, usize>)>> = OnceLock::new();
+    PROFILES.get_or_init(|| {
+        SAMPLES
+            .iter()
+            .map(|(language, sample)| {
+                let ranks = ranked_trigrams(sample)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(rank, trigram)| (trigram, rank))
+                    .collect();
+                (*language, ranks)
+            })
+            .collect()
+    })
+}
+
+/// Sum of rank differences between the text's trigrams and a language profile, with
+/// trigrams the profile lacks counted at the maximum penalty
+fn out_of_place(ranks: &[String], profile: &HashMap<String, usize>) -> usize {
+    ranks
+        .iter()
+        .enumerate()
+        .map(|(rank, trigram)| {
+            profile
+                .get(trigram)
+                .map_or(PROFILE_SIZE, |p| rank.abs_diff(*p))
+        })
+        .sum()
+}
+
+/// The most frequent character trigrams of `text`, most frequent first.
+///
+/// Words are lowercased and padded with spaces so trigrams also capture how words
+/// begin and end; anything that is not a letter separates words.
+fn ranked_trigrams(text: &str) -> Vec<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for word in text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+    {
+        let padded: Vec<char> = std::iter::once(' ')
+            .chain(word.chars().flat_map(char::to_lowercase))
+            .chain(std::iter::once(' '))
+            .collect();
+        for trigram in padded.windows(3) {
+            *counts.entry(trigram.iter().collect()).or_insert(0) += 1;
+        }
+    }
+
+    let mut ranked: Vec<(String, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(PROFILE_SIZE);
+    ranked.into_iter().map(|(trigram, _)| trigram).collect()
+}
+
+/// The whole text when short, otherwise windows starting a quarter, half and three
+/// quarters of the way through
+fn sample_windows(text: &str) -> String {
+    let total = text.chars().count();
+    if total <= WINDOW_CHARS * 3 {
+        return text.to_string();
+    }
+
+    [total / 4, total / 2, total * 3 / 4]
+        .iter()
+        .map(|start| {
+            text.chars()
+                .skip(*start)
+                .take(WINDOW_CHARS)
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Openings of public-domain novels as published by Project Gutenberg: Pride and
+    /// Prejudice, Die Verwandlung, Max Havelaar, Le Comte de Monte-Cristo, Don Quijote,
+    /// I promessi sposi and Dom Casmurro
+    const EXCERPTS: &[(&str, &str)] = &[
+        (
+            "en",
+            "It is a truth universally acknowledged, that a single man in possession of a good \
+             fortune, must be in want of a wife. However little known the feelings or views of \
+             such a man may be on his first entering a neighbourhood, this truth is so well fixed \
+             in the minds of the surrounding families, that he is considered the rightful \
+             property of some one or other of their daughters. \"My dear Mr. Bennet,\" said his \
+             lady to him one day, \"have you heard that Netherfield Park is let at last?\" Mr. \
+             Bennet replied that he had not. \"But it is,\" returned she; \"for Mrs. Long has \
+             just been here, and she told me all about it.\" Mr. Bennet made no answer. \"Do you \
+             not want to know who has taken it?\" cried his wife impatiently. \"You want to tell \
+             me, and I have no objection to hearing it.\" This was invitation enough.",
+        ),
+        (
+            "de",
+            "Als Gregor Samsa eines Morgens aus unruhigen Träumen erwachte, fand er sich in \
+             seinem Bett zu einem ungeheueren Ungeziefer verwandelt. Er lag auf seinem \
+             panzerartig harten Rücken und sah, wenn er den Kopf ein wenig hob, seinen \
+             gewölbten, braunen, von bogenförmigen Versteifungen geteilten Bauch, auf dessen \
+             Höhe sich die Bettdecke, zum gänzlichen Niedergleiten bereit, kaum noch erhalten \
+             konnte. Seine vielen, im Vergleich zu seinem sonstigen Umfang kläglich dünnen Beine \
+             flimmerten ihm hilflos vor den Augen. »Was ist mit mir geschehen?« dachte er. Es war \
+             kein Traum. Sein Zimmer, ein richtiges, nur etwas zu kleines Menschenzimmer, lag \
+             ruhig zwischen den vier wohlbekannten Wänden.",
+        ),
+        (
+            "nl",
+            "Ik ben makelaar in koffie, en woon op de Lauriergracht, No. 37. Het is mijn \
+             gewoonte niet, romans te schrijven of zulke dingen, en het heeft dan ook lang \
+             geduurd, voor ik er toe overging een paar riem papier extra te bestellen, en het \
+             werk aan te vangen, dat gij, lieve lezer, zoo-even in de hand hebt genomen, en dat \
+             ge lezen moet als ge makelaar in koffie zijt, of als ge wat anders zijt. Niet alleen \
+             dat ik nooit iets schreef wat naar een roman geleek, maar ik houd er zelfs niet van, \
+             iets dergelijks te lezen, omdat ik een man van zaken ben.",
+        ),
+        (
+            "fr",
+            "Le 24 février 1815, la vigie de Notre-Dame de la Garde signala le trois-mâts le \
+             Pharaon, venant de Smyrne, Trieste et Naples. Comme d'habitude, un pilote côtier \
+             partit aussitôt du port, rasa le château d'If, et alla aborder le navire entre le \
+             cap de Morgion et l'île de Rion. Aussitôt, comme d'habitude encore, la plate-forme \
+             du fort Saint-Jean s'était couverte de curieux; car c'est toujours une grande \
+             affaire à Marseille que l'arrivée d'un bâtiment, surtout quand ce bâtiment, comme le \
+             Pharaon, a été construit, gréé, arrimé sur les chantiers de la vieille Phocée, et \
+             appartient à un armateur de la ville.",
+        ),
+        (
+            "es",
+            "En un lugar de la Mancha, de cuyo nombre no quiero acordarme, no ha mucho tiempo \
+             que vivía un hidalgo de los de lanza en astillero, adarga antigua, rocín flaco y \
+             galgo corredor. Una olla de algo más vaca que carnero, salpicón las más noches, \
+             duelos y quebrantos los sábados, lentejas los viernes, algún palomino de añadidura \
+             los domingos, consumían las tres partes de su hacienda. El resto della concluían \
+             sayo de velarte, calzas de velludo para las fiestas con sus pantuflos de lo mismo, y \
+             los días de entre semana se honraba con su vellorí de lo más fino.",
+        ),
+        (
+            "it",
+            "Quel ramo del lago di Como, che volge a mezzogiorno, tra due catene non interrotte \
+             di monti, tutto a seni e a golfi, a seconda dello sporgere e del rientrare di \
+             quelli, vien, quasi a un tratto, a ristringersi, e a prender corso e figura di \
+             fiume, tra un promontorio a destra, e un'ampia costiera dall'altra parte; e il \
+             ponte, che ivi congiunge le due rive, par che renda ancor più sensibile all'occhio \
+             questa trasformazione, e segni il punto in cui il lago cessa, e l'Adda rincomincia, \
+             per ripigliar poi nome di lago dove le rive, allontanandosi di nuovo, lascian \
+             l'acqua distendersi e rallentarsi in nuovi golfi e in nuovi seni.",
+        ),
+        (
+            "pt",
+            "Uma noite destas, vindo da cidade para o Engenho Novo, encontrei no trem da Central \
+             um rapaz aqui do bairro, que eu conheço de vista e de chapéu. Cumprimentou-me, \
+             sentou-se ao pé de mim, falou da lua e dos ministros, e acabou recitando-me versos. \
+             A viagem era curta, e os versos pode ser que não fossem inteiramente maus. Sucedeu, \
+             porém, que, como eu estava cansado, fechei os olhos três ou quatro vezes; tanto \
+             bastou para que ele interrompesse a leitura e metesse os versos no bolso.",
+        ),
+    ];
+
+    #[test]
+    fn identifies_gutenberg_openings() {
+        for (expected, text) in EXCERPTS {
+            let detection = detect(text).expect("enough letters");
+            assert_eq!(detection.language, *expected);
+            assert!(
+                detection.confidence >= MIN_CONFIDENCE,
+                "{} detected with confidence {:.3}",
+                expected,
+                detection.confidence
+            );
+        }
+    }
+
+    #[test]
+    fn licence_boilerplate_does_not_decide_a_long_text() {
+        let header = "The Project Gutenberg eBook is for the use of anyone anywhere in the United \
+                      States and most other parts of the world at no cost and with almost no \
+                      restrictions whatsoever. ";
+        let (_, german) = EXCERPTS.iter().find(|(l, _)| *l == "de").unwrap();
+        let text = format!("{}{}", header.repeat(20), german.repeat(20));
+
+        assert_eq!(detect(&text).map(|d| d.language), Some("de"));
+    }
+}
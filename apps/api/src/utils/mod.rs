@@ -1,3 +1,10 @@
+pub mod audio;
+pub mod byte_range;
+pub mod chapters;
 pub mod errors;
+pub mod extractive;
+pub mod langid;
 pub mod text;
+pub mod timing;
 pub mod validators;
+pub mod wav;
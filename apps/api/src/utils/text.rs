@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 pub fn truncate_text(text: &str, max_words: usize) -> String {
     let words: Vec<&str> = text.split_whitespace().collect();
     if words.len() <= max_words {
@@ -6,3 +8,204 @@ pub fn truncate_text(text: &str, max_words: usize) -> String {
 
     words[..max_words].join(" ") + "..."
 }
+
+/// Keep whole sentences from the start of `text` while they fit in `max_words`.
+///
+/// Line breaks between kept sentences are preserved. Falls back to `truncate_text`
+/// when even the first sentence is too long.
+pub fn truncate_sentences(text: &str, max_words: usize, rules: &SentenceRules) -> String {
+    let mut words = 0;
+    let mut end = 0;
+
+    for span in sentence_spans(text, rules) {
+        let sentence_words = text[span.clone()].split_whitespace().count();
+        if words + sentence_words > max_words {
+            break;
+        }
+        words += sentence_words;
+        end = span.end;
+    }
+
+    if end == 0 {
+        truncate_text(text, max_words)
+    } else {
+        text[..end].to_string()
+    }
+}
+
+/// Drop Project Gutenberg headers, footers and production notes around a book's text.
+///
+/// Lines are trimmed and runs of blank lines collapse to one, so paragraphs and
+/// free-standing headings stay apart.
+pub fn strip_gutenberg_boilerplate(content: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut start_idx = 0;
+    let mut end_idx = lines.len();
+
+    // Find start of actual content
+    for (i, line) in lines.iter().enumerate() {
+        if line.contains("*** START OF") && line.contains("PROJECT GUTENBERG") {
+            start_idx = i + 1;
+            break;
+        }
+        if line.starts_with("CHAPTER") || line.starts_with("Chapter") {
+            start_idx = i;
+            break;
+        }
+    }
+
+    // Find end of actual content
+    for (i, line) in lines.iter().enumerate().rev() {
+        if line.contains("*** END OF") && line.contains("PROJECT GUTENBERG") {
+            end_idx = i;
+            break;
+        }
+    }
+
+    if start_idx >= end_idx {
+        return content.to_string();
+    }
+
+    let mut kept: Vec<&str> = Vec::new();
+    for line in lines[start_idx..end_idx].iter().map(|line| line.trim()) {
+        let boilerplate = line.starts_with("Produced by")
+            || line.starts_with("Updated:")
+            || line.contains("gutenberg.org")
+            || line.starts_with("[Illustration");
+        if boilerplate || (line.is_empty() && kept.last().is_none_or(|last| last.is_empty())) {
+            continue;
+        }
+        kept.push(line);
+    }
+    if kept.last().is_some_and(|last| last.is_empty()) {
+        kept.pop();
+    }
+
+    kept.join("\n")
+}
+
+/// Split text into pieces of at most `max_chars` characters that end on sentence boundaries.
+///
+/// Sentences are packed greedily; a single sentence longer than `max_chars` is broken at
+/// clause punctuation or, failing that, between words.
+pub fn split_sentence_chunks(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for sentence in split_keeping_delimiters(text, &['.', '!', '?']) {
+        let pieces = if sentence.chars().count() > max_chars {
+            split_long_sentence(&sentence, max_chars)
+        } else {
+            vec![sentence]
+        };
+
+        for piece in pieces {
+            let needed = current.chars().count() + piece.chars().count() + 1;
+            if !current.is_empty() && needed > max_chars {
+                chunks.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(&piece);
+        }
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+/// How a language marks the end of a sentence
+#[derive(Debug)]
+pub struct SentenceRules {
+    pub terminators: &'static [char],
+    /// Abbreviations whose final period does not end a sentence, e.g. "Dr."
+    pub abbreviations: &'static [&'static str],
+}
+
+/// Byte ranges of the sentences in `text`, with surrounding whitespace excluded
+pub fn sentence_spans(text: &str, rules: &SentenceRules) -> Vec<Range<usize>> {
+    delimited_spans(text, rules.terminators, rules.abbreviations)
+}
+
+/// Letters, digits and the combining vowel signs Indic scripts such as Tamil need
+pub fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '\u{0300}'..='\u{036F}' | '\u{0900}'..='\u{0DFF}')
+}
+
+fn delimited_spans(text: &str, delimiters: &[char], abbreviations: &[&str]) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut push_trimmed = |start: usize, end: usize| {
+        let piece = &text[start..end];
+        let leading = piece.len() - piece.trim_start().len();
+        let trimmed = piece.trim();
+        if !trimmed.is_empty() {
+            spans.push(start + leading..start + leading + trimmed.len());
+        }
+    };
+
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let at_boundary =
+            delimiters.contains(&c) && chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+        let end = i + c.len_utf8();
+        if at_boundary && !ends_with_abbreviation(&text[start..end], abbreviations) {
+            push_trimmed(start, end);
+            start = end;
+        }
+    }
+    push_trimmed(start, text.len());
+
+    spans
+}
+
+/// Whether `piece` ends with one of `abbreviations` standing as a whole word
+fn ends_with_abbreviation(piece: &str, abbreviations: &[&str]) -> bool {
+    abbreviations.iter().any(|abbreviation| {
+        piece.strip_suffix(abbreviation).is_some_and(|before| {
+            before
+                .chars()
+                .next_back()
+                .is_none_or(|c| c.is_whitespace() || c == '(' || c == '"')
+        })
+    })
+}
+
+fn split_keeping_delimiters(text: &str, delimiters: &[char]) -> Vec<String> {
+    delimited_spans(text, delimiters, &[])
+        .into_iter()
+        .map(|span| text[span].to_string())
+        .collect()
+}
+
+fn split_long_sentence(sentence: &str, max_chars: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+
+    for clause in split_keeping_delimiters(sentence, &[',', ';', ':']) {
+        for word in clause.split_whitespace() {
+            if !current.is_empty() && current.chars().count() + word.chars().count() + 1 > max_chars
+            {
+                pieces.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+        // Prefer to break after a clause once the piece is reasonably full
+        if current.chars().count() > max_chars / 2 {
+            pieces.push(std::mem::take(&mut current));
+        }
+    }
+
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+
+    pieces
+}
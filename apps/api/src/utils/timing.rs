@@ -0,0 +1,372 @@
+use std::ops::Range;
+
+use crate::{
+    models::{AudioTimings, TimedSegment},
+    utils::text::{self, SentenceRules},
+};
+
+/// Part of one sentence read within a TTS chunk; `weight` is its spoken length in characters
+#[derive(Debug, Clone, Copy)]
+pub struct SpeechUnit {
+    pub sentence: usize,
+    pub weight: usize,
+}
+
+/// Text sent to the TTS model in one request and the sentences it covers
+#[derive(Debug, Default)]
+pub struct PlannedChunk {
+    pub text: String,
+    pub units: Vec<SpeechUnit>,
+}
+
+/// Where a synthesized chunk landed in the joined audio
+pub struct SpokenChunk<'a> {
+    pub units: &'a [SpeechUnit],
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// Clean each sentence of `text` and pack the results into chunks of at most `max_chars`.
+///
+/// Sentences are never reordered, so every chunk records which sentences it reads and
+/// how much of each, letting the chunk's audio be mapped back onto the original text.
+pub fn plan_chunks(
+    text: &str,
+    sentences: &[Range<usize>],
+    max_chars: usize,
+    clean: impl Fn(&str) -> String,
+) -> Vec<PlannedChunk> {
+    let mut chunks = Vec::new();
+    let mut current = PlannedChunk::default();
+
+    for (index, span) in sentences.iter().enumerate() {
+        let cleaned = clean(&text[span.clone()]);
+        for piece in text::split_sentence_chunks(&cleaned, max_chars) {
+            let weight = piece.chars().count();
+            if !current.text.is_empty() && current.text.chars().count() + weight + 1 > max_chars {
+                chunks.push(std::mem::take(&mut current));
+            }
+            if !current.text.is_empty() {
+                current.text.push(' ');
+            }
+            current.text.push_str(&piece);
+            current.units.push(SpeechUnit {
+                sentence: index,
+                weight,
+            });
+        }
+    }
+
+    if !current.text.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+/// Map spoken chunks back onto the sentences and words of `text`.
+///
+/// A chunk's duration is shared between its sentences by spoken length, and a sentence's
+/// duration between its words the same way. Sentences that produced no speech are left out.
+pub fn align(text: &str, sentences: &[Range<usize>], chunks: &[SpokenChunk]) -> AudioTimings {
+    let mut spoken: Vec<Option<(f64, f64)>> = vec![None; sentences.len()];
+
+    for chunk in chunks {
+        let total: usize = chunk.units.iter().map(|unit| unit.weight.max(1)).sum();
+        let length = chunk.end_ms.saturating_sub(chunk.start_ms) as f64;
+        let mut cursor = chunk.start_ms as f64;
+
+        for unit in chunk.units {
+            let end = cursor + length * unit.weight.max(1) as f64 / total as f64;
+            let slot = &mut spoken[unit.sentence];
+            *slot = Some(match *slot {
+                Some((start, _)) => (start, end),
+                None => (cursor, end),
+            });
+            cursor = end;
+        }
+    }
+
+    let offsets = CharOffsets::new(text);
+    let mut timings = AudioTimings::default();
+
+    for (span, interval) in sentences.iter().zip(spoken) {
+        let Some((start, end)) = interval else {
+            continue;
+        };
+        timings
+            .sentences
+            .push(offsets.segment(text, span.clone(), start, end));
+
+        let sentence = &text[span.clone()];
+        let words: Vec<Range<usize>> = sentence
+            .split_whitespace()
+            .map(|word| {
+                let offset = span.start + (word.as_ptr() as usize - sentence.as_ptr() as usize);
+                offset..offset + word.len()
+            })
+            .collect();
+        // The trailing +1 stands in for the gap between words
+        let weight = |word: &Range<usize>| text[word.clone()].chars().count() + 1;
+        let total: usize = words.iter().map(weight).sum();
+
+        let mut cursor = start;
+        for word in words {
+            let word_end = cursor + (end - start) * weight(&word) as f64 / total as f64;
+            timings
+                .words
+                .push(offsets.segment(text, word, cursor, word_end));
+            cursor = word_end;
+        }
+    }
+
+    timings
+}
+
+/// Timings for audio without recorded chunk boundaries, spreading `duration_ms`
+/// across the whole text by length
+pub fn estimate(text: &str, rules: &SentenceRules, duration_ms: u64) -> AudioTimings {
+    let sentences = text::sentence_spans(text, rules);
+    let units: Vec<SpeechUnit> = sentences
+        .iter()
+        .enumerate()
+        .map(|(sentence, span)| SpeechUnit {
+            sentence,
+            weight: text[span.clone()].chars().count(),
+        })
+        .collect();
+
+    align(
+        text,
+        &sentences,
+        &[SpokenChunk {
+            units: &units,
+            start_ms: 0,
+            end_ms: duration_ms,
+        }],
+    )
+}
+
+/// Render sentence timings as a WebVTT caption track
+pub fn to_webvtt(timings: &AudioTimings) -> String {
+    let mut vtt = String::from("WEBVTT\n");
+
+    for (index, cue) in timings.sentences.iter().enumerate() {
+        vtt.push_str(&format!(
+            "\n{}\n{} --> {}\n{}\n",
+            index + 1,
+            vtt_timestamp(cue.start_ms),
+            vtt_timestamp(cue.end_ms),
+            escape_cue_text(&cue.text)
+        ));
+    }
+
+    vtt
+}
+
+fn vtt_timestamp(ms: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+fn escape_cue_text(text: &str) -> String {
+    // A blank line ends a cue, so keep each cue on one line
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Byte offset to character offset lookup for one text
+struct CharOffsets(Vec<usize>);
+
+impl CharOffsets {
+    fn new(text: &str) -> Self {
+        let mut offsets = vec![0; text.len() + 1];
+        let mut count = 0;
+        for (byte, _) in text.char_indices() {
+            offsets[byte] = count;
+            count += 1;
+        }
+        offsets[text.len()] = count;
+        Self(offsets)
+    }
+
+    fn segment(&self, text: &str, span: Range<usize>, start: f64, end: f64) -> TimedSegment {
+        TimedSegment {
+            text: text[span.clone()].to_string(),
+            start_ms: start.round() as u64,
+            end_ms: end.round() as u64,
+            char_start: self.0[span.start],
+            char_end: self.0[span.end],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans(text: &str, sentences: &[&str]) -> Vec<Range<usize>> {
+        sentences
+            .iter()
+            .map(|sentence| {
+                let start = text.find(sentence).unwrap();
+                start..start + sentence.len()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn plans_chunks_without_splitting_sentences_needlessly() {
+        let text = "One two. Three four five. Six.";
+        let sentences = spans(text, &["One two.", "Three four five.", "Six."]);
+        let chunks = plan_chunks(text, &sentences, 21, str::to_string);
+
+        let texts: Vec<&str> = chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, ["One two.", "Three four five. Six."]);
+        let units: Vec<(usize, usize)> = chunks[1]
+            .units
+            .iter()
+            .map(|u| (u.sentence, u.weight))
+            .collect();
+        assert_eq!(units, [(1, 16), (2, 4)]);
+    }
+
+    #[test]
+    fn aligns_sentences_and_words_on_character_offsets() {
+        let text = "Ça va. Über naïve Größe!";
+        let sentences = spans(text, &["Ça va.", "Über naïve Größe!"]);
+        let units = [
+            SpeechUnit {
+                sentence: 0,
+                weight: 6,
+            },
+            SpeechUnit {
+                sentence: 1,
+                weight: 18,
+            },
+        ];
+        let timings = align(
+            text,
+            &sentences,
+            &[SpokenChunk {
+                units: &units,
+                start_ms: 1000,
+                end_ms: 3400,
+            }],
+        );
+
+        let sentence_spans: Vec<(usize, usize, u64, u64)> = timings
+            .sentences
+            .iter()
+            .map(|s| (s.char_start, s.char_end, s.start_ms, s.end_ms))
+            .collect();
+        assert_eq!(sentence_spans, [(0, 6, 1000, 1600), (7, 24, 1600, 3400)]);
+
+        let words: Vec<(&str, usize, usize)> = timings
+            .words
+            .iter()
+            .map(|w| (w.text.as_str(), w.char_start, w.char_end))
+            .collect();
+        assert_eq!(
+            words,
+            [
+                ("Ça", 0, 2),
+                ("va.", 3, 6),
+                ("Über", 7, 11),
+                ("naïve", 12, 17),
+                ("Größe!", 18, 24),
+            ]
+        );
+        assert_eq!(timings.words[0].start_ms, 1000);
+        assert_eq!(timings.words[4].end_ms, 3400);
+    }
+
+    #[test]
+    fn sentences_split_across_chunks_span_both() {
+        let text = "First. Second part.";
+        let sentences = spans(text, &["First.", "Second part."]);
+        let first = [
+            SpeechUnit {
+                sentence: 0,
+                weight: 6,
+            },
+            SpeechUnit {
+                sentence: 1,
+                weight: 6,
+            },
+        ];
+        let second = [SpeechUnit {
+            sentence: 1,
+            weight: 5,
+        }];
+        let timings = align(
+            text,
+            &sentences,
+            &[
+                SpokenChunk {
+                    units: &first,
+                    start_ms: 0,
+                    end_ms: 1200,
+                },
+                SpokenChunk {
+                    units: &second,
+                    start_ms: 1500,
+                    end_ms: 2000,
+                },
+            ],
+        );
+
+        assert_eq!(timings.sentences[1].start_ms, 600);
+        assert_eq!(timings.sentences[1].end_ms, 2000);
+    }
+
+    #[test]
+    fn unspoken_sentences_are_left_out() {
+        let text = "Spoken. Skipped.";
+        let sentences = spans(text, &["Spoken.", "Skipped."]);
+        let units = [SpeechUnit {
+            sentence: 0,
+            weight: 7,
+        }];
+        let timings = align(
+            text,
+            &sentences,
+            &[SpokenChunk {
+                units: &units,
+                start_ms: 0,
+                end_ms: 700,
+            }],
+        );
+
+        assert_eq!(timings.sentences.len(), 1);
+        assert_eq!(timings.words.len(), 1);
+    }
+
+    #[test]
+    fn renders_webvtt_cues() {
+        let timings = AudioTimings {
+            sentences: vec![TimedSegment {
+                text: "Fish <&> chips\n\nto go".to_string(),
+                start_ms: 3_723_004,
+                end_ms: 3_725_500,
+                char_start: 0,
+                char_end: 21,
+            }],
+            words: Vec::new(),
+        };
+
+        assert_eq!(
+            to_webvtt(&timings),
+            "WEBVTT\n\n1\n01:02:03.004 --> 01:02:05.500\nFish &lt;&amp;&gt; chips to go\n"
+        );
+    }
+}
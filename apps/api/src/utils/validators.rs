@@ -1,17 +1,11 @@
-use crate::utils::errors::{AppError, Result};
+use crate::{
+    config::Languages,
+    utils::errors::{AppError, Result},
+};
 
-const SUPPORTED_LANGUAGES: &[&str] = &["en"];
-
-pub fn validate_language(lang: &str) -> Result<()> {
-    if SUPPORTED_LANGUAGES.contains(&lang) {
-        Ok(())
-    } else {
-        Err(AppError::InvalidInput(format!(
-            "Unsupported language: {}. Supported: {}",
-            lang,
-            SUPPORTED_LANGUAGES.join(", ")
-        )))
-    }
+/// Accept only the languages enabled in configuration
+pub fn validate_language(languages: &Languages, lang: &str) -> Result<()> {
+    languages.resolve(lang).map(|_| ())
 }
 
 pub fn validate_query(query: &str) -> Result<()> {
@@ -36,6 +30,34 @@ pub fn validate_query(query: &str) -> Result<()> {
     Ok(())
 }
 
+pub fn validate_format(format: &str) -> Result<()> {
+    const VALID_FORMATS: &[&str] = &["plain", "structured"];
+
+    if VALID_FORMATS.contains(&format) {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput(format!(
+            "Invalid format: {}. Valid formats: {}",
+            format,
+            VALID_FORMATS.join(", ")
+        )))
+    }
+}
+
+/// Summaries are between one page and `MAX_PAGES` pages long
+pub fn validate_max_pages(max_pages: usize) -> Result<()> {
+    const MAX_PAGES: usize = 10;
+
+    if (1..=MAX_PAGES).contains(&max_pages) {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput(format!(
+            "Invalid max_pages: {}. Must be between 1 and {}",
+            max_pages, MAX_PAGES
+        )))
+    }
+}
+
 pub fn validate_style(style: &str) -> Result<()> {
     const VALID_STYLES: &[&str] = &["concise", "detailed", "academic", "simple"];
 
@@ -49,3 +71,24 @@ pub fn validate_style(style: &str) -> Result<()> {
         )))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn validate_language_accepts_only_enabled_languages() {
+        let enabled = vec!["en".to_string(), "ta".to_string()];
+        let languages = Languages::new(&enabled, &HashMap::new(), &HashMap::new());
+
+        assert!(validate_language(&languages, "en").is_ok());
+        assert!(validate_language(&languages, "ta").is_ok());
+        for code in ["de", "fr", "", "english"] {
+            assert!(matches!(
+                validate_language(&languages, code),
+                Err(AppError::InvalidInput(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,465 @@
+use crate::utils::errors::{AppError, Result};
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_IEEE_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+/// Headers outside these bounds are treated as corrupt rather than trusted for sizes
+const MAX_CHANNELS: u16 = 8;
+const MIN_SAMPLE_RATE: u32 = 8_000;
+const MAX_SAMPLE_RATE: u32 = 192_000;
+
+/// Sample layout of a WAV stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WavFormat {
+    /// 1 for integer PCM, 3 for IEEE float (extensible headers are resolved to these)
+    pub audio_format: u16,
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub bits_per_sample: u16,
+}
+
+/// A parsed WAV file borrowing its sample data from the input
+pub struct Wav<'a> {
+    pub format: WavFormat,
+    pub data: &'a [u8],
+}
+
+impl Wav<'_> {
+    /// Number of sample frames (one sample per channel)
+    pub fn frame_count(&self) -> u64 {
+        (self.data.len() / frame_size(&self.format)) as u64
+    }
+
+    pub fn duration_ms(&self) -> u64 {
+        self.frame_count() * 1000 / self.format.sample_rate as u64
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> AppError {
+    AppError::ServiceError(format!("Invalid WAV audio: {}", reason.into()))
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+/// Walk the RIFF chunks of a WAV file and locate its `fmt ` and `data` chunks.
+///
+/// Streamed WAVs often carry a placeholder `data` size (0 or 0xFFFFFFFF); the data is
+/// then taken to run to the end of the input.
+pub fn parse(bytes: &[u8]) -> Result<Wav<'_>> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(invalid("missing RIFF/WAVE header"));
+    }
+
+    let mut format = None;
+    let mut offset = 12;
+
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let size = u32_at(bytes, offset + 4) as usize;
+        let body = offset + 8;
+
+        if id == b"fmt " {
+            if size < 16 || body + 16 > bytes.len() {
+                return Err(invalid("truncated fmt chunk"));
+            }
+            let mut audio_format = u16_at(bytes, body);
+            if audio_format == FORMAT_EXTENSIBLE {
+                // The real format is the first two bytes of the sub-format GUID
+                if size < 40 || body + 26 > bytes.len() {
+                    return Err(invalid("truncated extensible fmt chunk"));
+                }
+                audio_format = u16_at(bytes, body + 24);
+            }
+            format = Some(WavFormat {
+                audio_format,
+                channels: u16_at(bytes, body + 2),
+                sample_rate: u32_at(bytes, body + 4),
+                bits_per_sample: u16_at(bytes, body + 14),
+            });
+        } else if id == b"data" {
+            let format = format.ok_or_else(|| invalid("data chunk before fmt chunk"))?;
+            validate(&format)?;
+
+            let available = bytes.len() - body;
+            let size = if size == 0 || size > available {
+                available
+            } else {
+                size
+            };
+            let frame = frame_size(&format);
+            let data = &bytes[body..body + size - size % frame];
+            return Ok(Wav { format, data });
+        }
+
+        // Chunks are padded to an even length
+        offset = body.saturating_add(size).saturating_add(size % 2);
+    }
+
+    Err(invalid("no data chunk"))
+}
+
+fn validate(format: &WavFormat) -> Result<()> {
+    let supported = match format.audio_format {
+        FORMAT_PCM => matches!(format.bits_per_sample, 8 | 16 | 24 | 32),
+        FORMAT_IEEE_FLOAT => matches!(format.bits_per_sample, 32 | 64),
+        _ => false,
+    };
+    if !supported {
+        return Err(invalid(format!(
+            "unsupported encoding (format {}, {} bits)",
+            format.audio_format, format.bits_per_sample
+        )));
+    }
+    if format.channels == 0 || format.channels > MAX_CHANNELS {
+        return Err(invalid(format!(
+            "{} channels (supported: 1-{})",
+            format.channels, MAX_CHANNELS
+        )));
+    }
+    if !(MIN_SAMPLE_RATE..=MAX_SAMPLE_RATE).contains(&format.sample_rate) {
+        return Err(invalid(format!(
+            "sample rate {} Hz (supported: {}-{} Hz)",
+            format.sample_rate, MIN_SAMPLE_RATE, MAX_SAMPLE_RATE
+        )));
+    }
+    Ok(())
+}
+
+fn frame_size(format: &WavFormat) -> usize {
+    format.channels as usize * (format.bits_per_sample as usize / 8)
+}
+
+/// Decode interleaved samples to floats in [-1.0, 1.0]
+fn decode_samples(wav: &Wav) -> Vec<f32> {
+    let width = wav.format.bits_per_sample as usize / 8;
+    wav.data
+        .chunks_exact(width)
+        .map(|s| match (wav.format.audio_format, width) {
+            (FORMAT_PCM, 1) => (s[0] as f32 - 128.0) / 128.0,
+            (FORMAT_PCM, 2) => i16::from_le_bytes([s[0], s[1]]) as f32 / 32768.0,
+            (FORMAT_PCM, 3) => i32::from_le_bytes([0, s[0], s[1], s[2]]) as f32 / 2_147_483_648.0,
+            (FORMAT_PCM, _) => {
+                i32::from_le_bytes([s[0], s[1], s[2], s[3]]) as f32 / 2_147_483_648.0
+            }
+            (_, 4) => f32::from_le_bytes([s[0], s[1], s[2], s[3]]),
+            _ => f64::from_le_bytes([s[0], s[1], s[2], s[3], s[4], s[5], s[6], s[7]]) as f32,
+        })
+        .collect()
+}
+
+/// Mix or duplicate channels so interleaved `samples` have `to` channels
+fn convert_channels(samples: Vec<f32>, from: u16, to: u16) -> Vec<f32> {
+    if from == to {
+        return samples;
+    }
+
+    let (from, to) = (from as usize, to as usize);
+    samples
+        .chunks_exact(from)
+        .flat_map(|frame| {
+            let mono = frame.iter().sum::<f32>() / from as f32;
+            (0..to).map(move |c| {
+                if from == 1 || to == 1 {
+                    mono
+                } else {
+                    frame[c.min(from - 1)]
+                }
+            })
+        })
+        .collect()
+}
+
+/// Linear-interpolation resampling of interleaved `samples`
+fn resample(samples: Vec<f32>, channels: u16, from: u32, to: u32) -> Vec<f32> {
+    if from == to {
+        return samples;
+    }
+
+    let channels = channels as usize;
+    let frames = samples.len() / channels;
+    if frames == 0 {
+        return samples;
+    }
+
+    let out_frames = (frames as u64 * to as u64 / from as u64) as usize;
+    let step = from as f64 / to as f64;
+    let mut out = Vec::with_capacity(out_frames * channels);
+
+    for i in 0..out_frames {
+        let position = i as f64 * step;
+        let index = position as usize;
+        let fraction = (position - index as f64) as f32;
+        let next = (index + 1).min(frames - 1);
+        for c in 0..channels {
+            let a = samples[index * channels + c];
+            let b = samples[next * channels + c];
+            out.push(a + (b - a) * fraction);
+        }
+    }
+
+    out
+}
+
+/// Encode interleaved float samples as a 16-bit PCM WAV file.
+///
+/// Fails when the sizes do not fit the 32-bit fields of a WAV header.
+pub fn encode_pcm16(samples: &[f32], sample_rate: u32, channels: u16) -> Result<Vec<u8>> {
+    let too_large = || invalid("output too large for a WAV header");
+    let data_size = samples
+        .len()
+        .checked_mul(2)
+        .and_then(|size| u32::try_from(size).ok())
+        .ok_or_else(too_large)?;
+    let riff_size = data_size.checked_add(36).ok_or_else(too_large)?;
+    let block_align = channels.checked_mul(2).ok_or_else(too_large)?;
+    let byte_rate = sample_rate
+        .checked_mul(block_align as u32)
+        .ok_or_else(too_large)?;
+    let mut out = Vec::with_capacity(44 + data_size as usize);
+
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&riff_size.to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&FORMAT_PCM.to_le_bytes());
+    out.extend_from_slice(&channels.to_le_bytes());
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&byte_rate.to_le_bytes());
+    out.extend_from_slice(&block_align.to_le_bytes());
+    out.extend_from_slice(&16u16.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_size.to_le_bytes());
+
+    for sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * 32767.0).round() as i16;
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+
+    Ok(out)
+}
+
+/// Join WAV files into one 16-bit PCM WAV with `pause_ms` of silence between parts.
+///
+/// The output takes the sample rate and channel count of the first part; later parts
+/// with a different rate or channel count are converted to match.
+pub fn concatenate(parts: &[Vec<u8>], pause_ms: u32) -> Result<Vec<u8>> {
+    let wavs = parts
+        .iter()
+        .map(|part| parse(part))
+        .collect::<Result<Vec<_>>>()?;
+    let first = wavs
+        .first()
+        .ok_or_else(|| invalid("nothing to concatenate"))?
+        .format;
+
+    // `parse` bounds the rate and channel count, so the pause stays small
+    let pause_frames = first.sample_rate as u64 * pause_ms as u64 / 1000;
+    let pause = vec![0.0; pause_frames as usize * first.channels as usize];
+    let mut samples = Vec::new();
+
+    for (i, wav) in wavs.iter().enumerate() {
+        if wav.format.sample_rate != first.sample_rate || wav.format.channels != first.channels {
+            tracing::debug!(
+                "Converting WAV part {} from {} Hz x{} to {} Hz x{}",
+                i,
+                wav.format.sample_rate,
+                wav.format.channels,
+                first.sample_rate,
+                first.channels
+            );
+        }
+
+        let decoded = convert_channels(decode_samples(wav), wav.format.channels, first.channels);
+        let decoded = resample(
+            decoded,
+            first.channels,
+            wav.format.sample_rate,
+            first.sample_rate,
+        );
+
+        if i > 0 {
+            samples.extend_from_slice(&pause);
+        }
+        samples.extend(decoded);
+    }
+
+    encode_pcm16(&samples, first.sample_rate, first.channels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt_chunk(audio_format: u16, channels: u16, sample_rate: u32, bits: u16) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let mut chunk = b"fmt ".to_vec();
+        chunk.extend_from_slice(&16u32.to_le_bytes());
+        chunk.extend_from_slice(&audio_format.to_le_bytes());
+        chunk.extend_from_slice(&channels.to_le_bytes());
+        chunk.extend_from_slice(&sample_rate.to_le_bytes());
+        chunk.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        chunk.extend_from_slice(&block_align.to_le_bytes());
+        chunk.extend_from_slice(&bits.to_le_bytes());
+        chunk
+    }
+
+    fn riff(chunks: &[Vec<u8>]) -> Vec<u8> {
+        let body: Vec<u8> = chunks.concat();
+        let mut bytes = b"RIFF".to_vec();
+        bytes.extend_from_slice(&(body.len() as u32 + 4).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend(body);
+        bytes
+    }
+
+    fn chunk(id: &[u8; 4], declared_size: u32, body: &[u8]) -> Vec<u8> {
+        let mut chunk = id.to_vec();
+        chunk.extend_from_slice(&declared_size.to_le_bytes());
+        chunk.extend_from_slice(body);
+        chunk
+    }
+
+    fn error(bytes: &[u8]) -> String {
+        match parse(bytes) {
+            Err(AppError::ServiceError(message)) => message,
+            Err(other) => panic!("unexpected error: {}", other),
+            Ok(_) => panic!("parsed invalid WAV"),
+        }
+    }
+
+    #[test]
+    fn reads_format_and_duration() {
+        let bytes = riff(&[
+            fmt_chunk(FORMAT_PCM, 1, 16_000, 16),
+            chunk(b"data", 32_000, &[0; 32_000]),
+        ]);
+        let wav = parse(&bytes).unwrap();
+
+        assert_eq!(
+            wav.format,
+            WavFormat {
+                audio_format: FORMAT_PCM,
+                channels: 1,
+                sample_rate: 16_000,
+                bits_per_sample: 16,
+            }
+        );
+        assert_eq!(wav.frame_count(), 16_000);
+        assert_eq!(wav.duration_ms(), 1000);
+    }
+
+    #[test]
+    fn streamed_data_sizes_run_to_the_end_of_the_input() {
+        for placeholder in [0, 0xFFFF_FFFF] {
+            let bytes = riff(&[
+                fmt_chunk(FORMAT_PCM, 2, 8_000, 16),
+                chunk(b"data", placeholder, &[0; 3201]),
+            ]);
+            let wav = parse(&bytes).unwrap();
+
+            // The odd trailing byte is not a whole frame
+            assert_eq!(wav.data.len(), 3200);
+            assert_eq!(wav.duration_ms(), 100);
+        }
+    }
+
+    #[test]
+    fn skips_unknown_and_padded_chunks() {
+        let bytes = riff(&[
+            fmt_chunk(FORMAT_PCM, 1, 8_000, 8),
+            chunk(b"LIST", 3, &[1, 2, 3, 0]),
+            chunk(b"data", 8, &[128; 8]),
+        ]);
+
+        assert_eq!(parse(&bytes).unwrap().data, &[128; 8]);
+    }
+
+    #[test]
+    fn oversized_chunks_do_not_read_past_the_input() {
+        let bytes = riff(&[
+            fmt_chunk(FORMAT_PCM, 1, 8_000, 16),
+            chunk(b"LIST", 0xFFFF_FFF0, &[0; 16]),
+            chunk(b"data", 4, &[0; 4]),
+        ]);
+
+        assert!(error(&bytes).contains("no data chunk"));
+    }
+
+    #[test]
+    fn rejects_truncated_headers() {
+        assert!(error(b"RIFF\0\0\0\0WAV").contains("missing RIFF/WAVE header"));
+
+        let mut truncated_fmt = riff(&[fmt_chunk(FORMAT_PCM, 1, 8_000, 16)]);
+        truncated_fmt.truncate(truncated_fmt.len() - 4);
+        assert!(error(&truncated_fmt).contains("truncated fmt chunk"));
+
+        let mut extensible = fmt_chunk(FORMAT_EXTENSIBLE, 1, 8_000, 16);
+        extensible[4..8].copy_from_slice(&40u32.to_le_bytes());
+        assert!(error(&riff(&[extensible])).contains("truncated extensible fmt chunk"));
+
+        let data_first = riff(&[
+            chunk(b"data", 4, &[0; 4]),
+            fmt_chunk(FORMAT_PCM, 1, 8_000, 16),
+        ]);
+        assert!(error(&data_first).contains("data chunk before fmt chunk"));
+    }
+
+    #[test]
+    fn rejects_formats_outside_the_supported_bounds() {
+        let cases = [
+            (FORMAT_PCM, 0, 16_000, 16, "0 channels"),
+            (FORMAT_PCM, 9, 16_000, 16, "9 channels"),
+            (FORMAT_PCM, 1, 4_000, 16, "sample rate 4000 Hz"),
+            (FORMAT_PCM, 1, 384_000, 16, "sample rate 384000 Hz"),
+            (FORMAT_PCM, 1, 16_000, 12, "unsupported encoding"),
+            (FORMAT_IEEE_FLOAT, 1, 16_000, 16, "unsupported encoding"),
+            (2, 1, 16_000, 16, "unsupported encoding"),
+        ];
+        for (audio_format, channels, sample_rate, bits, reason) in cases {
+            let bytes = riff(&[
+                fmt_chunk(audio_format, channels, sample_rate, bits),
+                chunk(b"data", 4, &[0; 4]),
+            ]);
+            assert!(error(&bytes).contains(reason), "expected {}", reason);
+        }
+    }
+
+    #[test]
+    fn concatenates_with_pauses_and_converts_later_parts() {
+        let first = encode_pcm16(&[0.5; 1600], 16_000, 1).unwrap();
+        let second = encode_pcm16(&[-0.5; 1600], 8_000, 2).unwrap();
+
+        let joined = concatenate(&[first, second], 50).unwrap();
+        let wav = parse(&joined).unwrap();
+
+        assert_eq!(wav.format.sample_rate, 16_000);
+        assert_eq!(wav.format.channels, 1);
+        // 100 ms, a 50 ms pause, then 100 ms resampled from 8 kHz stereo
+        assert_eq!(wav.duration_ms(), 250);
+
+        let samples = decode_samples(&wav);
+        assert!((samples[0] - 0.5).abs() < 0.001);
+        assert_eq!(samples[1600], 0.0);
+        assert!((samples[samples.len() - 1] + 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn encoding_clamps_samples() {
+        let bytes = encode_pcm16(&[2.0, -2.0], 8_000, 1).unwrap();
+        let wav = parse(&bytes).unwrap();
+
+        assert_eq!(wav.data, [0xFF, 0x7F, 0x01, 0x80]);
+    }
+}
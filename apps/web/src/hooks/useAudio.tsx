@@ -1,6 +1,7 @@
 import { useState, useEffect, useRef } from "react";
 import { useMutation } from "@tanstack/react-query";
 import { Howl } from "howler";
+import { API_CONFIG } from "@/lib/constants";
 import { audioService } from "@/services/audioService";
 import type { AudioResponse } from "@/types";
 
@@ -69,32 +70,23 @@ export const useAudio = () => {
       console.log("Audio data received:", {
         id: data.id,
         language: data.language,
-        audioUrlLength: data.audio_url?.length || 0,
-        hasDataPrefix: data.audio_url?.startsWith("data:") || false,
+        audioUrl: data.audio_url,
+        fileSizeKb: data.file_size_kb,
       });
 
       // Validate audio URL
-      if (!data.audio_url || !data.audio_url.startsWith("data:audio/")) {
+      if (!data.audio_url) {
         const error =
-          "Audio was generated but in an invalid format. Please try again.";
-        console.error("Invalid audio format:", {
-          audioUrl: data.audio_url?.substring(0, 50),
-          hasAudioUrl: !!data.audio_url,
-          startsWithData: data.audio_url?.startsWith("data:") || false,
-        });
+          "Audio was generated but no stream URL was returned. Please try again.";
+        console.error("Missing audio URL:", { id: data.id });
         setAudioError(error);
         return;
       }
 
-      // Additional validation for audio data length
-      if (data.audio_url.length < 200) {
-        const error = "Audio file appears to be too small. Please try again.";
-        console.error("Audio data too small:", {
-          length: data.audio_url.length,
-        });
-        setAudioError(error);
-        return;
-      }
+      // The API returns a path to its streaming endpoint
+      const audioSrc = /^(https?:|data:)/.test(data.audio_url)
+        ? data.audio_url
+        : `${API_CONFIG.BASE_URL}${data.audio_url}`;
 
       if (howlRef.current) {
         howlRef.current.unload();
@@ -104,7 +96,7 @@ export const useAudio = () => {
 
       try {
         howlRef.current = new Howl({
-          src: [data.audio_url],
+          src: [audioSrc],
           format: ["wav", "mp3", "ogg"],
           html5: true,
           onplay: () => {
@@ -136,7 +128,7 @@ export const useAudio = () => {
             console.error("Howl load error:", {
               id,
               error,
-              audioUrlLength: data.audio_url?.length,
+              audioUrl: audioSrc,
             });
             setAudioError(errorMsg);
           },
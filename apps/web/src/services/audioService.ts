@@ -1,5 +1,5 @@
 import api from "@/services/api";
-import type { AudioResponse } from "@/types";
+import type { AudioResponse, AudioTimingsResponse, Voice } from "@/types";
 
 export const audioService = {
   getAudio: async (
@@ -17,4 +17,17 @@ export const audioService = {
     );
     return data;
   },
+
+  getTimings: async (audioId: string): Promise<AudioTimingsResponse> => {
+    const { data } = await api.get<AudioTimingsResponse>(
+      `/api/audio/${audioId}/timings`,
+    );
+    return data;
+  },
+
+  getVoices: async (language?: string): Promise<Voice[]> => {
+    const params = language ? `?language=${encodeURIComponent(language)}` : "";
+    const { data } = await api.get<Voice[]>(`/api/voices${params}`);
+    return data;
+  },
 };
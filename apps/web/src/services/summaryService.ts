@@ -1,11 +1,41 @@
 import api from "@/services/api";
-import type { SummaryRequest, SummaryResponse } from "@/types";
+import type {
+  JobAccepted,
+  JobResponse,
+  SummaryChaptersResponse,
+  SummaryRequest,
+  SummaryResponse,
+  TranslationRequest,
+} from "@/types";
 
 const MAX_RETRIES = 2;
 const RETRY_DELAY = 1000;
+const POLL_INTERVAL = 2000;
+const MAX_POLL_DURATION = 10 * 60 * 1000; // 10 minutes
 
 const delay = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
 
+const waitForJob = async (jobId: string): Promise<SummaryResponse> => {
+  const startedAt = Date.now();
+
+  while (Date.now() - startedAt < MAX_POLL_DURATION) {
+    const { data: job } = await api.get<JobResponse>(`/api/jobs/${jobId}`);
+
+    if (job.status === "done" && job.summary) {
+      return job.summary;
+    }
+
+    if (job.status === "failed") {
+      throw new Error(job.error || "Summary generation failed");
+    }
+
+    console.log(`Summary job ${jobId} is ${job.status}`);
+    await delay(POLL_INTERVAL);
+  }
+
+  throw new Error("Summary generation is taking too long. Please try again.");
+};
+
 export const summaryService = {
   generate: async (
     bookId: string,
@@ -21,16 +51,15 @@ export const summaryService = {
       try {
         console.log(`Summary attempt ${attempt}/${MAX_RETRIES}`);
 
-        const { data } = await api.post<SummaryResponse>(
+        const { data } = await api.post<JobAccepted>(
           `/api/books/${bookId}/summary`,
           request,
-          {
-            timeout: 180000, // 3 minutes for individual request
-          },
         );
 
+        const summary = await waitForJob(data.job_id);
+
         console.log(`Summary generated successfully on attempt ${attempt}`);
-        return data;
+        return summary;
       } catch (error: any) {
         console.warn(`Summary attempt ${attempt} failed:`, error.message);
         lastError = error;
@@ -63,4 +92,22 @@ export const summaryService = {
     console.error("All summary attempts failed:", errorMessage);
     throw new Error(errorMessage);
   },
+
+  getChapters: async (summaryId: string): Promise<SummaryChaptersResponse> => {
+    const { data } = await api.get<SummaryChaptersResponse>(
+      `/api/summary/${summaryId}/chapters`,
+    );
+    return data;
+  },
+
+  translate: async (
+    summaryId: string,
+    request: TranslationRequest,
+  ): Promise<SummaryResponse> => {
+    const { data } = await api.post<SummaryResponse>(
+      `/api/summaries/${summaryId}/translations`,
+      request,
+    );
+    return data;
+  },
 };
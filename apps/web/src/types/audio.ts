@@ -3,6 +3,16 @@ export interface AudioRequest {
   voice_type?: string;
 }
 
+export interface Voice {
+  id: string;
+  language: string;
+  model: string;
+  speaker_id?: string;
+  gender: string;
+  description: string;
+  is_default: boolean;
+}
+
 export interface AudioResponse {
   id: string;
   summary_id: string;
@@ -10,6 +20,28 @@ export interface AudioResponse {
   voice_type: string;
   duration_ms?: number;
   file_size_kb?: number;
+  content_type: string;
+  sample_rate?: number;
+  channels?: number;
+  bit_depth?: number;
+  is_placeholder: boolean;
   audio_url: string;
   created_at: string;
 }
+
+export interface TimedSegment {
+  text: string;
+  start_ms: number;
+  end_ms: number;
+  char_start: number;
+  char_end: number;
+}
+
+export interface AudioTimingsResponse {
+  audio_id: string;
+  summary_id: string;
+  duration_ms?: number;
+  estimated: boolean;
+  sentences: TimedSegment[];
+  words: TimedSegment[];
+}
@@ -1,6 +1,13 @@
 export interface SummaryRequest {
   language: string;
   style?: string;
+  max_pages?: number;
+  format?: "plain" | "structured";
+  force_regenerate?: boolean;
+}
+
+export interface TranslationRequest {
+  language: string;
 }
 
 export interface BookInfo {
@@ -9,11 +16,86 @@ export interface BookInfo {
   isbn?: string;
 }
 
+export interface SummaryProvenance {
+  content_origin:
+    | "full_text"
+    | "description"
+    | "fallback_content"
+    | "minimal_fallback";
+  input_chars: number;
+  truncated: boolean;
+  model: string;
+  generation_params: Record<string, unknown>;
+  chunk_count: number;
+  fallback_level: number;
+  detected_language?: string;
+  max_pages: number;
+}
+
+export interface StructuredCharacter {
+  name: string;
+  description: string;
+}
+
+export interface StructuredSummary {
+  overview: string;
+  plot_arc: string[];
+  characters: StructuredCharacter[];
+  themes: string[];
+  quotes: string[];
+  reading_level: string;
+}
+
 export interface SummaryResponse {
   id: string;
   summary_text: string;
   language: string;
   word_count: number;
+  version: number;
+  method: "abstractive" | "extractive";
+  format: "plain" | "structured";
+  structured?: StructuredSummary;
+  structured_text?: string;
+  translated_from?: string;
+  provenance: SummaryProvenance;
   book_info: BookInfo;
   created_at: string;
 }
+
+export interface ChapterSummary {
+  index: number;
+  title: string;
+  summary_text: string;
+  word_count: number;
+}
+
+export interface SummaryChaptersResponse {
+  summary: SummaryResponse;
+  chapters: ChapterSummary[];
+}
+
+export type JobStatus =
+  | "queued"
+  | "fetching_content"
+  | "chunking"
+  | "summarizing"
+  | "saving"
+  | "done"
+  | "failed";
+
+export interface JobAccepted {
+  job_id: string;
+  status: JobStatus;
+  status_url: string;
+  events_url: string;
+}
+
+export interface JobResponse {
+  id: string;
+  book_id: string;
+  status: JobStatus;
+  error?: string;
+  summary?: SummaryResponse;
+  created_at: string;
+  updated_at: string;
+}